@@ -5,9 +5,18 @@ use regex_automata::{
     util::syntax,
     MatchKind,
 };
-use std::{fs, io};
+use std::{collections::BTreeMap, fs, io};
 
-const MAC_ADDR_REGEX_STR: &str = "^(?:(?:[0-9A-Fa-f]{2}:){5}|(?:[0-9A-Fa-f]{2}-){5})[0-9A-Fa-f]{2}$";
+// accepted notations:
+//   XX:XX:XX:XX:XX:XX
+//   XX-XX-XX-XX-XX-XX
+//   XX XX XX XX XX XX
+//   XXXX.XXXX.XXXX (Cisco)
+//   XXXXXXXXXXXX
+const MAC_ADDR_REGEX_STR: &str = "^(?:(?:(?:[0-9A-Fa-f]{2}:){5}|(?:[0-9A-Fa-f]{2}-){5}|(?:[0-9A-Fa-f]{2} ){5})[0-9A-Fa-f]{2}|(?:[0-9A-Fa-f]{4}\\.){2}[0-9A-Fa-f]{4}|[0-9A-Fa-f]{12})$";
+
+// IEEE MA-L registry in the same csv format as https://standards-oui.ieee.org/oui/oui.csv
+const OUI_CSV_PATH: &str = "src/misc/oui.csv";
 
 fn main() -> io::Result<()> {
     #[cfg(windows)]
@@ -15,6 +24,9 @@ fn main() -> io::Result<()> {
         embed_resource::compile("icon.rc", embed_resource::NONE).manifest_required().unwrap();
     }
 
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={OUI_CSV_PATH}");
+
     let mac_addr_validation_dir = "src/mac_address/validation";
     fs::create_dir_all(mac_addr_validation_dir).unwrap();
 
@@ -63,7 +75,8 @@ fn main() -> io::Result<()> {
         };\n\
         \n";
 
-    let const_regex_str = format!(r#"pub(crate) const MAC_ADDR_REGEX_STR: &str = "{MAC_ADDR_REGEX_STR}";"#);
+    // debug formatting escapes the backslashes in the regex
+    let const_regex_str = format!("pub const MAC_ADDR_REGEX_STR: &str = {MAC_ADDR_REGEX_STR:?};");
 
     let autogenerated_mod_body = "\n\n\
         pub static MAC_ADDR_REGEX: Lazy<dense::DFA<&'static [u32]>> = Lazy::new(|| {\n\
@@ -79,7 +92,8 @@ fn main() -> io::Result<()> {
             \tdense_dfa\n\
         });\n\
         \n\
-        pub(crate) fn text_is_valid_mac<S: AsRef<[u8]>>(bytes: S) -> bool {\n\
+        /// Returns true if the bytes are a full MAC Address in any of the supported notations\n\
+        pub fn text_is_valid_mac<S: AsRef<[u8]>>(bytes: S) -> bool {\n\
             \tMAC_ADDR_REGEX\n\
                 \t\t.try_search_fwd(&Input::new(&bytes).anchored(Anchored::Yes).earliest(true))\n\
                 \t\t.is_ok_and(|x| x.is_some())\n\
@@ -95,5 +109,93 @@ fn main() -> io::Result<()> {
         "Unable to write file {mac_addr_validation_dir}/mod.rs"
     ));
 
+    generate_oui_table()
+}
+
+/// Splits a line of csv into fields, handling quoted fields that contain commas
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Generates a sorted table of OUIs with deduplicated vendor names, searched with a binary search at runtime
+fn generate_oui_table() -> io::Result<()> {
+    let oui_vendor_dir = "src/mac_address/vendor";
+    fs::create_dir_all(oui_vendor_dir).unwrap();
+
+    let oui_csv = fs::read_to_string(OUI_CSV_PATH)
+        .unwrap_or_else(|_| panic!("Unable to read file {OUI_CSV_PATH}"));
+
+    let mut oui_map: BTreeMap<u32, String> = BTreeMap::new();
+    // skip the header row, the file may start with a UTF-8 byte order mark
+    for line in oui_csv.trim_start_matches('\u{feff}').lines().skip(1) {
+        let fields = split_csv_line(line.trim_end_matches('\r'));
+        let (Some(registry), Some(assignment), Some(org_name)) = (fields.first(), fields.get(1), fields.get(2)) else {
+            continue;
+        };
+        // only 24 bit assignments are supported, MA-M & MA-S use longer prefixes
+        if registry != "MA-L" || assignment.len() != 6 {
+            continue;
+        }
+        let oui = u32::from_str_radix(assignment, 16)
+            .unwrap_or_else(|_| panic!("Invalid OUI assignment {assignment:?} in {OUI_CSV_PATH}"));
+        oui_map.entry(oui).or_insert_with(|| org_name.trim().to_string());
+    }
+
+    let mut vendor_names: Vec<&str> = oui_map.values().map(String::as_str).collect();
+    vendor_names.sort_unstable();
+    vendor_names.dedup();
+    assert!(vendor_names.len() <= u16::MAX as usize, "too many vendor names for a u16 index");
+
+    let mut oui_keys = String::new();
+    let mut oui_vendor_idx = String::new();
+    for (oui, org_name) in oui_map.iter() {
+        let idx = vendor_names.binary_search(&org_name.as_str()).unwrap();
+        oui_keys.push_str(&format!("\t0x{oui:06X},\n"));
+        oui_vendor_idx.push_str(&format!("\t{idx},\n"));
+    }
+    let vendor_names_str: String = vendor_names
+        .iter()
+        .map(|name| format!("\t{name:?},\n"))
+        .collect();
+
+    let oui_len = oui_map.len();
+    let vendor_names_len = vendor_names.len();
+    let autogenerated_mod_string = format!("\
+        // DO NOT EDIT THIS FILE. IT WAS AUTOMATICALLY GENERATED BY THE BUILD SCRIPT\n\n\
+        static VENDOR_NAMES: [&str; {vendor_names_len}] = [\n{vendor_names_str}];\n\n\
+        static OUI_KEYS: [u32; {oui_len}] = [\n{oui_keys}];\n\n\
+        static OUI_VENDOR_IDX: [u16; {oui_len}] = [\n{oui_vendor_idx}];\n\n\
+        /// Vendor registered to the OUI in the IEEE MA-L registry\n\
+        pub fn lookup_oui(oui: u32) -> Option<&'static str> {{\n\
+            \tOUI_KEYS\n\
+                \t\t.binary_search(&oui)\n\
+                \t\t.ok()\n\
+                \t\t.map(|i| VENDOR_NAMES[OUI_VENDOR_IDX[i] as usize])\n\
+        }}\n");
+
+    fs::write(
+        format!("{oui_vendor_dir}/mod.rs"),
+        autogenerated_mod_string,
+    )
+    .unwrap_or_else(|_| panic!(
+        "Unable to write file {oui_vendor_dir}/mod.rs"
+    ));
+
     Ok(())
 }
@@ -0,0 +1,856 @@
+mod output;
+
+use unifi_search_tool::{
+    csv::csv_field,
+    mac_address::{MacAddress, MacCase, MacFormat, MacNotation},
+    unifi::{
+        api::{UnifiAPIError, UnifiClient},
+        builder::UnifiClientBuilder,
+        cancel::CancellationToken,
+        devices::{DeviceType, UnifiDeviceBasic, UnifiSite},
+        profiles::{ControllerProfile, ControllerProfiles, ProfilesError},
+        progress::SearchEvent,
+        proxy::{ProxyConfig, ProxyMode},
+        search::{
+            find_unifi_device, get_client_and_login, get_sites_to_search, MacQuery, UnifiController, UnifiSearchInfo,
+        },
+        server_url::ServerUrl,
+        site_filter::{SiteFilter, SitePattern},
+        unknown_models::UnknownModels,
+    },
+};
+use clap::{Args, Parser, Subcommand};
+use output::{write_controllers, write_error, write_search_matches, write_sites, ErrorReport, OutputFormat};
+use reqwest::Url;
+use std::{
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+use zeroize::Zeroize;
+
+/// Exit codes, so scripts can branch on the outcome of a command
+const EXIT_FOUND: u8 = 0;
+const EXIT_NOT_FOUND: u8 = 1;
+/// Also used by clap when the arguments can't be parsed
+const EXIT_USAGE: u8 = 2;
+const EXIT_AUTH_FAILURE: u8 = 3;
+const EXIT_NETWORK_FAILURE: u8 = 4;
+const EXIT_ERROR: u8 = 5;
+
+/// Search for a device by MAC Address across every site in a Unifi controller.
+///
+/// The GUI is opened when no arguments are given.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    #[command(flatten)]
+    credentials: Credentials,
+    /// Output format of `search` & `sites`, `export` always writes CSV
+    #[arg(long, short = 'f', value_enum, default_value_t = OutputFormat::Table, global = true)]
+    format: OutputFormat,
+    /// How MAC Addresses are written in every output format, ie `xx-xx-xx-xx-xx-xx` or `XXXX.XXXX.XXXX`.
+    /// The case of the `X`s is the case of the hex digits
+    #[arg(long, value_name = "FORMAT", value_parser = parse_mac_format, default_value = "XX:XX:XX:XX:XX:XX", global = true)]
+    mac_format: MacFormat,
+    #[command(flatten)]
+    site_selection: SiteSelection,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args, Debug)]
+struct Credentials {
+    /// Unifi Controller URL, including the port unless it runs on 80/443
+    #[arg(long, short = 's', env = "UNIFI_SERVER_URL", global = true)]
+    server_url: Option<String>,
+    /// Unifi Controller username
+    #[arg(long, short = 'u', env = "UNIFI_USERNAME", global = true)]
+    username: Option<String>,
+    /// Unifi Controller password, prefer --password-stdin or UNIFI_PASSWORD so it isn't in your shell history
+    #[arg(long, short = 'p', env = "UNIFI_PASSWORD", hide_env_values = true, global = true)]
+    password: Option<String>,
+    /// Read the password from the first line of stdin, used instead of --password or UNIFI_PASSWORD
+    #[arg(long, global = true)]
+    password_stdin: bool,
+    /// Accept invalid HTTPS certificates, ie self-signed certificates
+    #[arg(long, short = 'k', env = "UNIFI_ACCEPT_INVALID_CERTS", global = true)]
+    accept_invalid_certs: bool,
+    /// Proxy to connect through, ie http://proxy:3128 or socks5h://jump:1080.
+    /// HTTPS_PROXY, HTTP_PROXY, ALL_PROXY, & NO_PROXY are used when not given
+    #[arg(long, env = "UNIFI_PROXY", global = true)]
+    proxy: Option<String>,
+    /// Username for the proxy
+    #[arg(long, env = "UNIFI_PROXY_USERNAME", global = true, requires = "proxy")]
+    proxy_username: Option<String>,
+    /// Password for the proxy
+    #[arg(long, env = "UNIFI_PROXY_PASSWORD", hide_env_values = true, global = true, requires = "proxy")]
+    proxy_password: Option<String>,
+    /// Connect directly, ignoring the proxy environment variables
+    #[arg(long, global = true, conflicts_with = "proxy")]
+    no_proxy: bool,
+}
+
+/// Which sites are used by `search`, `sites`, & `export`
+#[derive(Args, Debug)]
+struct SiteSelection {
+    /// Use every site in the controller, including sites the account isn't assigned to. Requires a super admin
+    #[arg(long, global = true)]
+    all_sites: bool,
+    /// Only use sites whose code or description matches, ie `lab-*`, `desc:*Branch*`, or `code:re:^cust-\d+$`. Can be repeated
+    #[arg(long = "include-site", value_name = "PATTERN", global = true)]
+    include_sites: Vec<SitePattern>,
+    /// Skip sites whose code or description matches, the same patterns as --include-site. Can be repeated
+    #[arg(long = "exclude-site", value_name = "PATTERN", global = true)]
+    exclude_sites: Vec<SitePattern>,
+    /// Save --include-site & --exclude-site for this server URL, they're used when neither is given.
+    /// Saving without either clears the saved filter
+    #[arg(long, global = true)]
+    save_site_filter: bool,
+    /// Ignore the site filter saved for this server URL
+    #[arg(long, global = true, conflicts_with = "save_site_filter")]
+    no_site_filter: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Search every site for a MAC Address. Exits with 0 if found & 1 if not found
+    Search(SearchArgs),
+    /// List every site in the controller
+    Sites,
+    /// Export every device in every site as CSV
+    Export {
+        /// File to write the CSV to, stdout if not given
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Manage the controllers saved to be searched together with `search --controller`
+    Controllers {
+        #[command(subcommand)]
+        action: ControllersAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ControllersAction {
+    /// List the saved controllers
+    List,
+    /// Save the controller in --server-url with --username & --accept-invalid-certs, the password is never saved.
+    /// Replaces a saved controller with the same name or server URL
+    Add {
+        /// Name shown with each result, ie `emea`
+        name: String,
+        /// Environment variable holding this controller's password, --password or --password-stdin is used if it isn't set
+        #[arg(long, value_name = "VARIABLE")]
+        password_env: Option<String>,
+    },
+    /// Forget a saved controller, its site filter is kept
+    Remove { name: String },
+}
+
+#[derive(Args, Debug)]
+struct SearchArgs {
+    /// Full MAC Address, partial MAC Address with `*` or `?` or only the last 6 or more hex digits, range like ff:ff:ff:00:00:00/24, or SLAAC IPv6 address
+    mac: String,
+    /// Also search the clients known to each site
+    #[arg(long, short = 'c')]
+    clients: bool,
+    /// Only match devices of this type, ie uap, usw, or ugw
+    #[arg(long, short = 't', value_parser = parse_device_type)]
+    device_type: Option<DeviceType>,
+    /// Also match devices within this many addresses of a full MAC Address
+    #[arg(long, short = 'n', default_value_t = 0)]
+    nearby: u8,
+    /// Don't show the progress of the search, it's only shown when stderr is a terminal
+    #[arg(long, short = 'q')]
+    quiet: bool,
+    /// Search this saved controller instead of --server-url, see the `controllers` command. Can be repeated
+    #[arg(long = "controller", value_name = "NAME")]
+    controllers: Vec<String>,
+    /// Search every saved controller instead of --server-url
+    #[arg(long, conflicts_with = "controllers")]
+    all_controllers: bool,
+}
+
+impl SearchArgs {
+    #[inline]
+    fn uses_saved_controllers(&self) -> bool {
+        self.all_controllers || !self.controllers.is_empty()
+    }
+}
+
+/// Accepts the API type code or the name of any known device type, ie `uap` or `access point`
+fn parse_device_type(input: &str) -> Result<DeviceType, String> {
+    DeviceType::KNOWN
+        .iter()
+        .find(|device_type| {
+            device_type.as_str().eq_ignore_ascii_case(input) || device_type.name().eq_ignore_ascii_case(input)
+        })
+        .cloned()
+        .ok_or_else(|| {
+            let codes: Vec<&str> = DeviceType::KNOWN.iter().map(|device_type| device_type.as_str()).collect();
+            format!("expected one of {}", codes.join(", "))
+        })
+}
+
+/// Template of a MAC format, with each hex digit written as `X` or `x`, ie `XX:XX:XX:XX:XX:XX`
+fn mac_format_template(mac_format: MacFormat) -> String {
+    let digit_placeholder = match mac_format.case {
+        MacCase::Upper => "X",
+        MacCase::Lower => "x",
+    };
+    MacAddress::new(0).format(mac_format).to_string().replace('0', digit_placeholder)
+}
+
+/// Accepts the template of any MAC format, ie `xx-xx-xx-xx-xx-xx`
+fn parse_mac_format(input: &str) -> Result<MacFormat, String> {
+    let mac_formats = MacCase::ALL
+        .into_iter()
+        .flat_map(|case| MacNotation::ALL.into_iter().map(move |notation| MacFormat::new(notation, case)));
+    mac_formats
+        .clone()
+        .find(|mac_format| mac_format_template(*mac_format) == input)
+        .ok_or_else(|| {
+            let templates: Vec<String> = mac_formats.map(|mac_format| format!("{:?}", mac_format_template(mac_format))).collect();
+            format!("expected one of {}", templates.join(", "))
+        })
+}
+
+/// Returns true if arguments were passed, meaning the command line interface should run instead of the GUI
+#[cfg(feature = "gui")]
+pub(crate) fn is_cli_invocation() -> bool {
+    std::env::args_os().nth(1).is_some()
+}
+
+pub(crate) fn run() -> ExitCode {
+    attach_parent_console();
+    let Cli { mut credentials, format, mac_format, site_selection, command } = Cli::parse();
+
+    let proxy = credentials.proxy_mode();
+    let all_sites = site_selection.all_sites;
+    let command_result = match command {
+        Command::Controllers { action } => run_controllers(action, &credentials, format),
+        Command::Search(search_args) if search_args.uses_saved_controllers() => {
+            saved_controllers(&search_args, &mut credentials, &site_selection).and_then(|controllers| {
+                let connection_info = UnifiSearchInfo { controllers, proxy, all_sites, ..Default::default() };
+                run_search(search_args, format, mac_format, connection_info)
+            })
+        }
+        command => credentials
+            .resolve()
+            .map_err(CliError::InvalidInput)
+            .and_then(|(username, password, server_url)| {
+                let site_filter = site_selection.site_filter(&server_url)?;
+                let client_builder = UnifiClient::builder(&server_url)
+                    .accept_invalid_certs(credentials.accept_invalid_certs)
+                    .proxy(proxy.clone());
+                match command {
+                    Command::Search(search_args) => {
+                        // the rest of the search is filled in from the search arguments
+                        let connection_info = UnifiSearchInfo {
+                            controllers: vec![UnifiController {
+                                username,
+                                password,
+                                server_url,
+                                accept_invalid_certs: credentials.accept_invalid_certs,
+                                site_filter,
+                                ..Default::default()
+                            }],
+                            proxy,
+                            all_sites,
+                            ..Default::default()
+                        };
+                        run_search(search_args, format, mac_format, connection_info)
+                    }
+                    Command::Sites => run_sites(format, client_builder, username, password, all_sites, &site_filter),
+                    Command::Export { output } => {
+                        run_export(output, mac_format, client_builder, username, password, all_sites, &site_filter)
+                    }
+                    Command::Controllers { .. } => unreachable!("the controllers command doesn't log in"),
+                }
+            }),
+    };
+    // commands that don't log in never take the password out of the arguments
+    if let Some(mut unused_password) = credentials.password.take() {
+        unused_password.zeroize();
+    }
+    ExitCode::from(command_result.unwrap_or_else(|cli_error| cli_error.report(format)))
+}
+
+/// The saved controllers chosen with `--controller` or `--all-controllers`, each with its own account & certificate setting.
+/// The shared `--username` & password are used by controllers saved without them
+fn saved_controllers(
+    search_args: &SearchArgs,
+    credentials: &mut Credentials,
+    site_selection: &SiteSelection,
+) -> Result<Vec<UnifiController>, CliError> {
+    let controller_profiles = ControllerProfiles::load()?;
+    let mut profiles: Vec<&ControllerProfile> = Vec::new();
+    match search_args.all_controllers {
+        true => profiles.extend(controller_profiles.controllers()),
+        false => {
+            for name in search_args.controllers.iter() {
+                let profile = controller_profiles.controller(name).ok_or_else(|| {
+                    CliError::InvalidInput(
+                        format!("no controller is saved as {:?}, see `controllers list`", name).into_boxed_str(),
+                    )
+                })?;
+                if !profiles.iter().any(|added| added.server_url == profile.server_url) {
+                    profiles.push(profile);
+                }
+            }
+        }
+    }
+    if profiles.is_empty() {
+        return Err(CliError::InvalidInput(Box::from(
+            "no controllers are saved, save one with `controllers add`",
+        )));
+    }
+
+    // stdin can only be read once, so the shared password is kept until every controller has a copy
+    let mut shared_password_option: Option<String> = None;
+    let mut controllers = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        let username = match profile.username {
+            Some(ref username) => username.to_string(),
+            None => credentials.username().map_err(CliError::InvalidInput)?,
+        };
+        let env_password_option = profile.password_env.as_deref().and_then(|variable| std::env::var(variable).ok());
+        let password = match (env_password_option, &shared_password_option) {
+            (Some(password), _) => password,
+            (None, Some(shared_password)) => shared_password.clone(),
+            (None, None) => {
+                let shared_password = credentials.password().map_err(CliError::InvalidInput)?;
+                shared_password_option.insert(shared_password).clone()
+            }
+        };
+        controllers.push(UnifiController {
+            name: profile.name.to_string(),
+            username,
+            password,
+            server_url: profile.server_url.to_string(),
+            accept_invalid_certs: profile.accept_invalid_certs || credentials.accept_invalid_certs,
+            site_filter: site_selection.site_filter(&profile.server_url)?,
+        });
+    }
+    if let Some(mut shared_password) = shared_password_option {
+        shared_password.zeroize();
+    }
+    Ok(controllers)
+}
+
+fn run_controllers(
+    action: ControllersAction,
+    credentials: &Credentials,
+    output_format: OutputFormat,
+) -> Result<u8, CliError> {
+    let mut controller_profiles = ControllerProfiles::load()?;
+    match action {
+        ControllersAction::List => {
+            let profiles: Vec<&ControllerProfile> = controller_profiles.controllers().collect();
+            write_controllers(&mut io::stdout().lock(), output_format, &profiles)?;
+        }
+        ControllersAction::Add { name, password_env } => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(CliError::InvalidInput(Box::from("the controller name can't be empty")));
+            }
+            let server_url = credentials.server_url().map_err(CliError::InvalidInput)?;
+            ServerUrl::parse(&server_url)?;
+            let profile = controller_profiles.save_controller(name, &server_url);
+            profile.username = credentials.username.as_deref().map(Box::from);
+            profile.accept_invalid_certs = credentials.accept_invalid_certs;
+            profile.password_env = password_env.as_deref().map(Box::from);
+            let saved_url = profile.server_url.clone();
+            controller_profiles.save()?;
+            eprintln!("Saved controller {} ({}) to {}", name, saved_url, ControllerProfiles::file_path().display());
+        }
+        ControllersAction::Remove { name } => {
+            let Some(saved_name) = controller_profiles.controller(&name).map(|profile| profile.name.clone()) else {
+                return Err(CliError::InvalidInput(
+                    format!("no controller is saved as {:?}, see `controllers list`", name).into_boxed_str(),
+                ));
+            };
+            controller_profiles.remove_controller(&saved_name);
+            controller_profiles.save()?;
+            eprintln!("Removed controller {}", saved_name);
+        }
+    }
+    Ok(EXIT_FOUND)
+}
+
+impl SiteSelection {
+    /// The site filter from the arguments, or the one saved for the server URL if neither pattern argument is given
+    fn site_filter(&self, server_url: &str) -> Result<SiteFilter, CliError> {
+        let site_filter = SiteFilter::new(self.include_sites.clone(), self.exclude_sites.clone());
+        if self.save_site_filter {
+            let mut controller_profiles = ControllerProfiles::load()?;
+            controller_profiles.get_or_insert(server_url).site_filter = site_filter.clone();
+            controller_profiles.save()?;
+            eprintln!("Saved the site filter to {}", ControllerProfiles::file_path().display());
+            return Ok(site_filter);
+        }
+        if !site_filter.is_empty() || self.no_site_filter {
+            return Ok(site_filter);
+        }
+        let saved_site_filter = ControllerProfiles::load()?.site_filter(server_url);
+        if !saved_site_filter.is_empty() {
+            eprintln!("Using the site filter saved for {}, use --no-site-filter to ignore it", server_url);
+        }
+        Ok(saved_site_filter)
+    }
+}
+
+impl Credentials {
+    fn proxy_mode(&mut self) -> ProxyMode {
+        match (&self.proxy, self.no_proxy) {
+            (Some(proxy_url), _) => ProxyMode::Custom(ProxyConfig {
+                url: proxy_url.clone(),
+                username: self.proxy_username.clone(),
+                // moved so the only copy is the one the client uses
+                password: self.proxy_password.take(),
+            }),
+            (None, true) => ProxyMode::Disabled,
+            (None, false) => ProxyMode::System,
+        }
+    }
+
+    /// Username, password, & server URL, each is required
+    fn resolve(&mut self) -> Result<(String, String, String), Box<str>> {
+        let server_url = self.server_url()?;
+        let username = self.username()?;
+        let password = self.password()?;
+        Ok((username, password, server_url))
+    }
+
+    fn server_url(&self) -> Result<String, Box<str>> {
+        self.server_url
+            .clone()
+            .ok_or_else(|| Box::from("a server URL is required, use --server-url or UNIFI_SERVER_URL"))
+    }
+
+    fn username(&self) -> Result<String, Box<str>> {
+        self.username
+            .clone()
+            .ok_or_else(|| Box::from("a username is required, use --username or UNIFI_USERNAME"))
+    }
+
+    /// Reads stdin with `--password-stdin`, so it can only be called once.
+    /// The password is taken out of the arguments, so no other copy is left behind
+    fn password(&mut self) -> Result<String, Box<str>> {
+        choose_password(self.password.take(), self.password_stdin, io::stdin().lock())
+    }
+}
+
+/// `--password-stdin` takes priority over `--password` & UNIFI_PASSWORD, which is zeroized when it isn't used
+fn choose_password(
+    password_option: Option<String>,
+    password_stdin: bool,
+    mut stdin: impl BufRead,
+) -> Result<String, Box<str>> {
+    match (password_option, password_stdin) {
+        (password_option, true) => {
+            if let Some(mut unused_password) = password_option {
+                unused_password.zeroize();
+            }
+            let mut line = String::new();
+            stdin
+                .read_line(&mut line)
+                .map_err(|e| format!("unable to read the password from stdin, {}", e))?;
+            let password = line.trim_end_matches(['\r', '\n']).to_string();
+            line.zeroize();
+            Ok(password)
+        }
+        (Some(password), false) => Ok(password),
+        (None, false) => Err(Box::from("a password is required, use --password, --password-stdin, or UNIFI_PASSWORD")),
+    }
+}
+
+/// Errors from a command, each maps to an exit code
+enum CliError {
+    Api(UnifiAPIError),
+    InvalidInput(Box<str>),
+    Io(io::Error),
+    Profiles(ProfilesError),
+}
+
+impl CliError {
+    /// Writes the error in the output format & returns the exit code
+    fn report(self, output_format: OutputFormat) -> u8 {
+        let error_report = match self {
+            CliError::Api(ref unifi_api_error) => {
+                let exit_code = match unifi_api_error {
+                    UnifiAPIError::InvalidUrl { .. } | UnifiAPIError::InvalidProxy { .. } => EXIT_USAGE,
+                    UnifiAPIError::LoginAuthenticationError { .. } => EXIT_AUTH_FAILURE,
+                    UnifiAPIError::ReqwestError { .. } => EXIT_NETWORK_FAILURE,
+                    UnifiAPIError::ClientError { .. }
+                    | UnifiAPIError::RuntimeError { .. }
+                    | UnifiAPIError::JsonError { .. }
+                    | UnifiAPIError::Canceled => EXIT_ERROR,
+                };
+                ErrorReport::from_api_error(unifi_api_error, exit_code)
+            }
+            CliError::InvalidInput(ref error_desc) => ErrorReport {
+                kind: "invalid_input",
+                message: error_desc.to_string(),
+                url: None,
+                source: None,
+                exit_code: EXIT_USAGE,
+            },
+            CliError::Io(ref source) => ErrorReport {
+                kind: "io_error",
+                message: source.to_string(),
+                url: None,
+                source: None,
+                exit_code: EXIT_ERROR,
+            },
+            CliError::Profiles(ref source) => ErrorReport {
+                kind: "io_error",
+                message: source.to_string(),
+                url: None,
+                source: None,
+                exit_code: EXIT_ERROR,
+            },
+        };
+        let exit_code = error_report.exit_code;
+        // nothing else can be done if the error can't be written
+        let _ = write_error(output_format, error_report);
+        exit_code
+    }
+}
+
+impl From<UnifiAPIError> for CliError {
+    fn from(source: UnifiAPIError) -> Self {
+        CliError::Api(source)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(source: io::Error) -> Self {
+        CliError::Io(source)
+    }
+}
+
+impl From<ProfilesError> for CliError {
+    fn from(source: ProfilesError) -> Self {
+        CliError::Profiles(source)
+    }
+}
+
+fn run_search(
+    search_args: SearchArgs,
+    output_format: OutputFormat,
+    mac_format: MacFormat,
+    connection_info: UnifiSearchInfo,
+) -> Result<u8, CliError> {
+    let SearchArgs { mac, clients, device_type, nearby, quiet, .. } = search_args;
+    let mac_query = MacQuery::parse(mac.trim(), nearby).map_err(CliError::InvalidInput)?;
+
+    let all_sites = connection_info.all_sites;
+    let mut search_info = UnifiSearchInfo {
+        mac_query,
+        device_type_filter: device_type,
+        search_clients: clients,
+        ..connection_info
+    };
+    let mut progress_line = ProgressLine::new(!quiet && io::stderr().is_terminal(), !quiet);
+    // if the existing list can't be read, nothing is recorded so the file isn't overwritten
+    let mut unknown_models_result = UnknownModels::load();
+    let search_result = find_unifi_device(
+        &mut search_info,
+        |event: &SearchEvent| {
+            if let Ok(unknown_models) = unknown_models_result.as_mut() {
+                unknown_models.record_event(event);
+            }
+            progress_line.update(event)
+        },
+        &CancellationToken::new(),
+    );
+    progress_line.clear();
+    let unknown_models_saved = unknown_models_result.and_then(|unknown_models| match unknown_models.is_empty() {
+        true => Ok(()),
+        false => unknown_models.save(),
+    });
+    if let Err(unknown_models_error) = unknown_models_saved {
+        eprintln!("warning: unable to record unknown device models, {}", unknown_models_error);
+    }
+    let search_matches = search_result?;
+    for failed_controller in search_matches.failed_controllers.iter() {
+        eprintln!("warning: unable to search {}, {}", failed_controller.name, failed_controller.error);
+    }
+    if all_sites {
+        warn_if_not_all_sites(search_matches.sites.total);
+    }
+    if search_matches.is_empty() {
+        eprintln!("Unable to find device with MAC Address {}", search_info.mac_query);
+        match search_matches.sites.not_searched() {
+            Some(0) => eprintln!("Searched {}", search_matches.sites),
+            Some(_) => eprintln!("Searched {}, use --all-sites to search the sites this account isn't assigned to", search_matches.sites),
+            None => eprintln!("Searched {}, this account may not have access to every site", search_matches.sites),
+        }
+        // json always has output, so wrappers don't need to check the exit code before parsing
+        if output_format != OutputFormat::Json {
+            return Ok(EXIT_NOT_FOUND);
+        }
+    }
+
+    write_search_matches(&mut io::stdout().lock(), output_format, mac_format, &search_info.mac_query, &search_matches)?;
+    match search_matches.is_empty() {
+        true => Ok(EXIT_NOT_FOUND),
+        false => Ok(EXIT_FOUND),
+    }
+}
+
+/// Shows the site being searched on a single line of stderr, overwritten as the search progresses
+struct ProgressLine {
+    enabled: bool,
+    /// The port chosen by probing is reported even when stderr isn't a terminal, unless `--quiet` is used
+    report_probed_url: bool,
+    /// Name of the controller being searched, ie `emea (1 of 2): `, empty when searching a single controller
+    controller_prefix: String,
+    sites_done: usize,
+    sites_total: usize,
+    /// Characters written to the line, so a shorter line can cover the previous one
+    line_len: usize,
+}
+
+impl ProgressLine {
+    fn new(enabled: bool, report_probed_url: bool) -> Self {
+        Self { enabled, report_probed_url, controller_prefix: String::new(), sites_done: 0, sites_total: 0, line_len: 0 }
+    }
+
+    fn update(&mut self, event: &SearchEvent) {
+        let line = match event {
+            SearchEvent::ControllerStarted { name, index, count } => {
+                self.sites_done = 0;
+                self.sites_total = 0;
+                if *count > 1 {
+                    self.controller_prefix = format!("{} ({} of {}): ", name, index + 1, count);
+                }
+                String::from("Logging in")
+            }
+            SearchEvent::ServerUrlResolved { url, probed } => {
+                if *probed && self.report_probed_url {
+                    self.clear();
+                    eprintln!("{}", probed_url_message(url));
+                }
+                return;
+            }
+            SearchEvent::LoggedIn => String::from("Logged in, getting sites"),
+            SearchEvent::ControllerIdentified(_) => return,
+            SearchEvent::SitesListed(n) => {
+                self.sites_total = *n;
+                return;
+            }
+            SearchEvent::SiteStarted(site) => {
+                format!("Searching site {} of {}: {}", self.sites_done + 1, self.sites_total, site.desc)
+            }
+            SearchEvent::SiteFinished(..) => {
+                self.sites_done += 1;
+                return;
+            }
+            SearchEvent::Retrying { site, attempt, .. } => {
+                format!("Network error searching {}, retrying (attempt {})", site.desc, attempt)
+            }
+            SearchEvent::UnknownModel { .. }
+            | SearchEvent::Match(_)
+            | SearchEvent::ControllerFinished { .. }
+            | SearchEvent::ControllerFailed { .. } => return,
+        };
+        let line = format!("{}{}", self.controller_prefix, line);
+        self.write(&line);
+    }
+
+    /// Removes the progress line so it isn't mixed in with the output
+    fn clear(&mut self) {
+        if self.line_len > 0 {
+            self.write("");
+            let _ = io::stderr().write_all(b"\r");
+        }
+    }
+
+    fn write(&mut self, line: &str) {
+        if !self.enabled {
+            return;
+        }
+        let len = line.chars().count();
+        let _ = write!(io::stderr(), "\r{}{:pad$}", line, "", pad = self.line_len.saturating_sub(len));
+        self.line_len = len;
+    }
+}
+
+fn login(
+    client_builder: UnifiClientBuilder,
+    mut username: String,
+    mut password: String,
+) -> Result<UnifiClient, CliError> {
+    let client = get_client_and_login(client_builder, &mut username, &mut password, &CancellationToken::new())?;
+    if client.server_url_was_probed() {
+        eprintln!("{}", probed_url_message(client.server_url()));
+    }
+    Ok(client)
+}
+
+/// Notes on stderr when sites are left out, so they aren't mistaken for every site in the controller
+fn list_sites(client: &UnifiClient, all_sites: bool, site_filter: &SiteFilter) -> Result<Vec<UnifiSite>, CliError> {
+    let (mut unifi_sites, sites_total) = get_sites_to_search(client, all_sites)?;
+    let sites_listed = unifi_sites.len();
+    unifi_sites.retain(|site| site_filter.matches(site));
+    if unifi_sites.len() < sites_listed {
+        eprintln!("Skipped {} sites with the site filter", sites_listed - unifi_sites.len());
+    }
+    match sites_total {
+        Some(total) if total > sites_listed => eprintln!(
+            "Using {} of {} sites, use --all-sites to include the sites this account isn't assigned to",
+            sites_listed,
+            total
+        ),
+        None if all_sites => warn_if_not_all_sites(None),
+        _ => {}
+    }
+    Ok(unifi_sites)
+}
+
+/// `--all-sites` needs a super admin, the total number of sites is only known when it's allowed
+fn warn_if_not_all_sites(sites_total: Option<usize>) {
+    if sites_total.is_none() {
+        eprintln!("warning: --all-sites requires a super admin, only the sites assigned to this account were used");
+    }
+}
+
+fn probed_url_message(url: &Url) -> String {
+    format!("No port was given, using {}", url.as_str().trim_end_matches('/'))
+}
+
+fn run_sites(
+    output_format: OutputFormat,
+    client_builder: UnifiClientBuilder,
+    username: String,
+    password: String,
+    all_sites: bool,
+    site_filter: &SiteFilter,
+) -> Result<u8, CliError> {
+    let client = login(client_builder, username, password)?;
+    let unifi_sites = list_sites(&client, all_sites, site_filter)?;
+
+    write_sites(&mut io::stdout().lock(), output_format, &unifi_sites)?;
+    Ok(EXIT_FOUND)
+}
+
+fn run_export(
+    output: Option<PathBuf>,
+    mac_format: MacFormat,
+    client_builder: UnifiClientBuilder,
+    username: String,
+    password: String,
+    all_sites: bool,
+    site_filter: &SiteFilter,
+) -> Result<u8, CliError> {
+    let client = login(client_builder, username, password)?;
+    let unifi_sites = list_sites(&client, all_sites, site_filter)?;
+
+    let mut writer: Box<dyn Write> = match output {
+        Some(ref path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    writeln!(writer, "Site,Site Code,Name,MAC Address,Type,Model,Label,Status")?;
+    for site in unifi_sites.iter() {
+        let site_devices = client.get_site_devices_basic(&site.code)?;
+        for mut unifi_device in site_devices {
+            unifi_device.create_device_label();
+            write_device_csv_row(&mut writer, mac_format, &site.desc, &site.code, &unifi_device)?;
+        }
+    }
+    writer.flush()?;
+
+    if let Some(path) = output {
+        eprintln!("Exported devices to {}", path.display());
+    }
+    Ok(EXIT_FOUND)
+}
+
+fn write_device_csv_row<W: Write>(
+    writer: &mut W,
+    mac_format: MacFormat,
+    site_desc: &str,
+    site_code: &str,
+    unifi_device: &UnifiDeviceBasic,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{},{},{},{},{},{},{},{}",
+        csv_field(site_desc),
+        csv_field(site_code),
+        csv_field(unifi_device.name_option.as_deref().unwrap_or("")),
+        unifi_device.mac.format(mac_format),
+        csv_field(unifi_device.device_type.name()),
+        csv_field(&unifi_device.device_model),
+        csv_field(unifi_device.device_label_option.unwrap_or("")),
+        unifi_device.state.as_str(),
+    )
+}
+
+/// Release builds on Windows use the GUI subsystem, which doesn't have a console.
+/// Attach to the console of the shell that started the program, so output is visible
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(dw_process_id: u32) -> i32;
+    }
+    // SAFETY: AttachConsole has no preconditions, it fails harmlessly if there's no parent console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_stdin_overrides_the_password_argument() {
+        let password = choose_password(Some(String::from("from-env")), true, "from-stdin\r\n".as_bytes());
+        assert_eq!(password.as_deref(), Ok("from-stdin"));
+    }
+
+    #[test]
+    fn password_argument_without_stdin() {
+        assert_eq!(choose_password(Some(String::from("from-env")), false, "unused\n".as_bytes()).as_deref(), Ok("from-env"));
+        assert_eq!(choose_password(None, true, "".as_bytes()).as_deref(), Ok(""));
+        assert!(choose_password(None, false, "unused\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn password_stdin_is_accepted_with_a_password() {
+        let cli = Cli::try_parse_from(["unifi-search-tool", "--password", "secret", "--password-stdin", "sites"])
+            .expect("--password-stdin should be accepted alongside --password or UNIFI_PASSWORD");
+        assert!(cli.credentials.password_stdin);
+    }
+
+    #[test]
+    fn mac_format_templates() {
+        assert_eq!(parse_mac_format("XX:XX:XX:XX:XX:XX"), Ok(MacFormat::default()));
+        assert_eq!(parse_mac_format("xxxx.xxxx.xxxx"), Ok(MacFormat::new(MacNotation::Dot, MacCase::Lower)));
+        assert_eq!(parse_mac_format("XXXXXXXXXXXX"), Ok(MacFormat::new(MacNotation::Bare, MacCase::Upper)));
+        assert!(parse_mac_format("XX:xx:XX:XX:XX:XX").is_err());
+        assert!(parse_mac_format("colon").is_err());
+    }
+
+    #[test]
+    fn export_rows_use_the_mac_format() {
+        let unifi_device: UnifiDeviceBasic = serde_json::from_str(
+            r#"{"mac": "74:83:c2:3f:a2:10", "type": "uap", "model": "U7PG2", "state": 1, "adopted": true}"#,
+        )
+        .expect("the device should deserialize");
+        let mut row = Vec::new();
+        write_device_csv_row(&mut row, MacFormat::new(MacNotation::Hyphen, MacCase::Lower), "Default", "default", &unifi_device)
+            .expect("writing to a Vec shouldn't fail");
+        let row = String::from_utf8(row).expect("the row should be utf-8");
+        assert!(row.contains(",74-83-c2-3f-a2-10,"), "{}", row);
+    }
+}
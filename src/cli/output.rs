@@ -0,0 +1,435 @@
+use unifi_search_tool::{
+    csv::csv_field,
+    mac_address::{FormattedMac, MacAddress, MacFormat},
+    unifi::{
+        api::UnifiAPIError,
+        devices::{ClientDevice, UnifiDeviceBasic, UnifiSite},
+        profiles::ControllerProfile,
+        search::{MacQuery, UnifiSearchMatches},
+    },
+};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+/// Incremented whenever a field in the JSON output is removed or changes meaning, new fields may be added at any time
+const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(super) enum OutputFormat {
+    /// Aligned columns for reading in a terminal
+    #[default]
+    Table,
+    /// A single JSON object with a stable schema, errors are also written to stdout as JSON
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+#[derive(Serialize)]
+struct JsonSite<'a> {
+    code: &'a str,
+    desc: &'a str,
+    /// Only present when several controllers are searched
+    #[serde(skip_serializing_if = "str::is_empty")]
+    controller: &'a str,
+}
+
+impl<'a> From<&'a UnifiSite> for JsonSite<'a> {
+    fn from(site: &'a UnifiSite) -> Self {
+        Self { code: &site.code, desc: &site.desc, controller: &site.controller }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDevice<'a> {
+    site: JsonSite<'a>,
+    mac: FormattedMac,
+    name: Option<&'a str>,
+    /// Type code used by the Unifi API, ie `uap`
+    device_type: &'a str,
+    device_type_name: &'a str,
+    model: &'a str,
+    label: Option<&'static str>,
+    state: &'static str,
+    adopted: bool,
+    gateway_mode: bool,
+    vendor: Option<&'static str>,
+    match_type: &'static str,
+    /// Only present for nearby searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct JsonClient<'a> {
+    site: JsonSite<'a>,
+    mac: FormattedMac,
+    name: Option<&'a str>,
+    hostname: Option<&'a str>,
+    vendor: Option<&'static str>,
+    last_ip: Option<&'a str>,
+    network: Option<&'a str>,
+    is_wired: Option<bool>,
+    first_seen: Option<DateTime<Utc>>,
+    last_seen: Option<DateTime<Utc>>,
+    match_type: &'static str,
+    /// Only present for nearby searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct JsonSearchOutput<'a> {
+    schema_version: u32,
+    query: String,
+    found: bool,
+    sites_searched: usize,
+    /// Every site in the controller, `null` unless the account is a super admin
+    sites_total: Option<usize>,
+    /// Sites left out by the site filter
+    sites_excluded: usize,
+    devices: Vec<JsonDevice<'a>>,
+    clients: Vec<JsonClient<'a>>,
+    /// Controllers skipped because of an error, when several controllers are searched
+    failed_controllers: Vec<JsonFailedController<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFailedController<'a> {
+    controller: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonSitesOutput<'a> {
+    schema_version: u32,
+    sites: Vec<JsonSite<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonControllersOutput<'a> {
+    schema_version: u32,
+    controllers: Vec<JsonController<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonController<'a> {
+    name: &'a str,
+    server_url: &'a str,
+    username: Option<&'a str>,
+    accept_invalid_certs: bool,
+    password_env: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonErrorOutput<'a> {
+    schema_version: u32,
+    error: JsonError<'a>,
+}
+
+/// `kind` is stable & meant for branching on, `message` is for humans
+#[derive(Serialize)]
+struct JsonError<'a> {
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    exit_code: u8,
+}
+
+/// An error from a command, in a form that every output format can display
+pub(super) struct ErrorReport<'a> {
+    pub(super) kind: &'static str,
+    pub(super) message: String,
+    pub(super) url: Option<&'a str>,
+    pub(super) source: Option<String>,
+    pub(super) exit_code: u8,
+}
+
+impl<'a> ErrorReport<'a> {
+    /// `kind` for each `UnifiAPIError` variant
+    pub(super) fn from_api_error(unifi_api_error: &'a UnifiAPIError, exit_code: u8) -> Self {
+        let (kind, url, source) = match unifi_api_error {
+            UnifiAPIError::ClientError { source } => ("client_error", None, Some(source.to_string())),
+            UnifiAPIError::InvalidUrl { url, .. } => ("invalid_input", Some(url.as_ref()), None),
+            UnifiAPIError::InvalidProxy { .. } => ("invalid_input", None, None),
+            UnifiAPIError::LoginAuthenticationError { url } => ("authentication_failed", Some(url.as_ref()), None),
+            UnifiAPIError::ReqwestError { source } => (
+                "network_error",
+                source.url().map(|url| url.as_str()),
+                Some(source.to_string()),
+            ),
+            UnifiAPIError::JsonError { url, source } => ("invalid_response", Some(url.as_ref()), Some(source.to_string())),
+            UnifiAPIError::RuntimeError { source } => ("client_error", None, Some(source.to_string())),
+            UnifiAPIError::Canceled => ("canceled", None, None),
+        };
+        Self {
+            kind,
+            message: unifi_api_error.to_string(),
+            url,
+            source,
+            exit_code,
+        }
+    }
+}
+
+pub(super) fn write_error(output_format: OutputFormat, error_report: ErrorReport) -> io::Result<()> {
+    match output_format {
+        OutputFormat::Json => {
+            let ErrorReport { kind, message, url, source, exit_code } = error_report;
+            let json_output = JsonErrorOutput {
+                schema_version: JSON_SCHEMA_VERSION,
+                error: JsonError { kind, message, url, source, exit_code },
+            };
+            write_json(&mut io::stdout().lock(), &json_output)
+        }
+        OutputFormat::Table | OutputFormat::Csv => {
+            let mut stderr = io::stderr().lock();
+            writeln!(stderr, "error: {}", error_report.message)?;
+            if let Some(source) = error_report.source {
+                writeln!(stderr, "{}", source)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub(super) fn write_search_matches<W: Write>(
+    writer: &mut W,
+    output_format: OutputFormat,
+    mac_format: MacFormat,
+    mac_query: &MacQuery,
+    search_matches: &UnifiSearchMatches,
+) -> io::Result<()> {
+    match output_format {
+        OutputFormat::Json => {
+            let json_output = JsonSearchOutput {
+                schema_version: JSON_SCHEMA_VERSION,
+                query: mac_query.to_string(),
+                found: !search_matches.is_empty(),
+                sites_searched: search_matches.sites.searched,
+                sites_total: search_matches.sites.total,
+                sites_excluded: search_matches.sites.excluded,
+                devices: search_matches.devices.iter().map(|unifi_device| json_device(mac_format, mac_query, unifi_device)).collect(),
+                clients: search_matches.clients.iter().map(|client_device| json_client(mac_format, mac_query, client_device)).collect(),
+                failed_controllers: search_matches
+                    .failed_controllers
+                    .iter()
+                    .map(|failed_controller| JsonFailedController {
+                        controller: &failed_controller.name,
+                        message: &failed_controller.error,
+                    })
+                    .collect(),
+            };
+            write_json(writer, &json_output)
+        }
+        OutputFormat::Table | OutputFormat::Csv => {
+            const HEADERS: [&str; 11] = [
+                "Controller", "Kind", "Site", "Site Code", "Name", "MAC Address", "Type", "Model", "Status", "Last IP", "Match",
+            ];
+            let device_rows = search_matches.devices.iter().map(|unifi_device| {
+                [
+                    Cow::Borrowed(unifi_device.site.controller.as_ref()),
+                    Cow::Borrowed("device"),
+                    Cow::Borrowed(unifi_device.site.desc.as_ref()),
+                    Cow::Borrowed(unifi_device.site.code.as_ref()),
+                    Cow::Borrowed(unifi_device.name_option.as_deref().unwrap_or("")),
+                    Cow::Owned(unifi_device.mac.format(mac_format).to_string()),
+                    Cow::Borrowed(unifi_device.device_type.name()),
+                    Cow::Borrowed(unifi_device.device_label_option.unwrap_or(&unifi_device.device_model)),
+                    Cow::Borrowed(unifi_device.state.as_str()),
+                    Cow::Borrowed(""),
+                    match_cell(mac_query, unifi_device.mac),
+                ]
+            });
+            let client_rows = search_matches.clients.iter().map(|client_device| {
+                [
+                    Cow::Borrowed(client_device.site.controller.as_ref()),
+                    Cow::Borrowed("client"),
+                    Cow::Borrowed(client_device.site.desc.as_ref()),
+                    Cow::Borrowed(client_device.site.code.as_ref()),
+                    Cow::Borrowed(client_device.display_name().unwrap_or("")),
+                    Cow::Owned(client_device.mac.format(mac_format).to_string()),
+                    Cow::Borrowed("Client"),
+                    Cow::Borrowed(""),
+                    Cow::Borrowed(""),
+                    Cow::Borrowed(client_device.last_ip.as_deref().unwrap_or("")),
+                    match_cell(mac_query, client_device.mac),
+                ]
+            });
+            let rows: Vec<[Cow<str>; 11]> = device_rows.chain(client_rows).collect();
+            // the controller column is only needed when several controllers were searched
+            match rows.iter().any(|row| !row[0].is_empty()) {
+                true => write_rows(writer, output_format, &HEADERS, &rows),
+                false => {
+                    let rows: Vec<[Cow<str>; 10]> = rows.into_iter().map(without_first_column).collect();
+                    write_rows(writer, output_format, &without_first_column(HEADERS), &rows)
+                }
+            }
+        }
+    }
+}
+
+/// Drops the first field of a row
+fn without_first_column<T, const N: usize, const M: usize>(row: [T; N]) -> [T; M] {
+    debug_assert_eq!(N, M + 1);
+    let mut fields = row.into_iter().skip(1);
+    std::array::from_fn(|_| fields.next().expect("the row should have one more field than the result"))
+}
+
+pub(super) fn write_sites<W: Write>(
+    writer: &mut W,
+    output_format: OutputFormat,
+    unifi_sites: &[UnifiSite],
+) -> io::Result<()> {
+    match output_format {
+        OutputFormat::Json => {
+            let json_output = JsonSitesOutput {
+                schema_version: JSON_SCHEMA_VERSION,
+                sites: unifi_sites.iter().map(JsonSite::from).collect(),
+            };
+            write_json(writer, &json_output)
+        }
+        OutputFormat::Table | OutputFormat::Csv => {
+            let rows: Vec<[Cow<str>; 2]> = unifi_sites
+                .iter()
+                .map(|site| [Cow::Borrowed(site.code.as_ref()), Cow::Borrowed(site.desc.as_ref())])
+                .collect();
+            write_rows(writer, output_format, &["Code", "Description"], &rows)
+        }
+    }
+}
+
+pub(super) fn write_controllers<W: Write>(
+    writer: &mut W,
+    output_format: OutputFormat,
+    profiles: &[&ControllerProfile],
+) -> io::Result<()> {
+    match output_format {
+        OutputFormat::Json => {
+            let json_output = JsonControllersOutput {
+                schema_version: JSON_SCHEMA_VERSION,
+                controllers: profiles
+                    .iter()
+                    .map(|profile| JsonController {
+                        name: &profile.name,
+                        server_url: &profile.server_url,
+                        username: profile.username.as_deref(),
+                        accept_invalid_certs: profile.accept_invalid_certs,
+                        password_env: profile.password_env.as_deref(),
+                    })
+                    .collect(),
+            };
+            write_json(writer, &json_output)
+        }
+        OutputFormat::Table | OutputFormat::Csv => {
+            let rows: Vec<[Cow<str>; 5]> = profiles
+                .iter()
+                .map(|profile| {
+                    [
+                        Cow::Borrowed(profile.name.as_ref()),
+                        Cow::Borrowed(profile.server_url.as_ref()),
+                        Cow::Borrowed(profile.username.as_deref().unwrap_or("")),
+                        Cow::Borrowed(if profile.accept_invalid_certs { "yes" } else { "no" }),
+                        Cow::Borrowed(profile.password_env.as_deref().unwrap_or("")),
+                    ]
+                })
+                .collect();
+            write_rows(writer, output_format, &["Name", "Server URL", "Username", "Invalid Certs", "Password Env"], &rows)
+        }
+    }
+}
+
+fn json_device<'a>(mac_format: MacFormat, mac_query: &MacQuery, unifi_device: &'a UnifiDeviceBasic) -> JsonDevice<'a> {
+    JsonDevice {
+        site: JsonSite::from(&unifi_device.site),
+        mac: unifi_device.mac.format(mac_format),
+        name: unifi_device.name_option.as_deref(),
+        device_type: unifi_device.device_type.as_str(),
+        device_type_name: unifi_device.device_type.name(),
+        model: &unifi_device.device_model,
+        label: unifi_device.device_label_option,
+        state: unifi_device.state.as_str(),
+        adopted: unifi_device.adopted,
+        gateway_mode: unifi_device.gateway_mode.unwrap_or(false),
+        vendor: unifi_device.mac.vendor(),
+        match_type: mac_query.match_type(unifi_device.mac).as_str(),
+        offset: mac_query.offset_of(unifi_device.mac),
+    }
+}
+
+fn json_client<'a>(mac_format: MacFormat, mac_query: &MacQuery, client_device: &'a ClientDevice) -> JsonClient<'a> {
+    JsonClient {
+        site: JsonSite::from(&client_device.site),
+        mac: client_device.mac.format(mac_format),
+        name: client_device.name_option.as_deref(),
+        hostname: client_device.hostname_option.as_deref(),
+        vendor: client_device.mac.vendor(),
+        last_ip: client_device.last_ip.as_deref(),
+        network: client_device.network_name.as_deref(),
+        is_wired: client_device.is_wired,
+        first_seen: client_device.first_seen,
+        last_seen: client_device.last_seen,
+        match_type: mac_query.match_type(client_device.mac).as_str(),
+        offset: mac_query.offset_of(client_device.mac),
+    }
+}
+
+/// Match type, with the offset for nearby searches, ie `nearby +2`
+fn match_cell(mac_query: &MacQuery, mac: MacAddress) -> Cow<'static, str> {
+    let match_type = mac_query.match_type(mac).as_str();
+    match mac_query.offset_of(mac) {
+        Some(offset) if offset != 0 => Cow::Owned(format!("{} {:+}", match_type, offset)),
+        _ => Cow::Borrowed(match_type),
+    }
+}
+
+fn write_json<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, value)?;
+    writeln!(writer)
+}
+
+fn write_rows<W: Write, const N: usize>(
+    writer: &mut W,
+    output_format: OutputFormat,
+    headers: &[&str; N],
+    rows: &[[Cow<str>; N]],
+) -> io::Result<()> {
+    match output_format {
+        OutputFormat::Csv => {
+            writeln!(writer, "{}", headers.join(","))?;
+            for row in rows {
+                let fields: Vec<Cow<str>> = row.iter().map(|field| csv_field(field)).collect();
+                writeln!(writer, "{}", fields.join(","))?;
+            }
+        }
+        _ => {
+            // pad each column to its widest value
+            let mut widths = headers.map(|header| header.chars().count());
+            for row in rows {
+                for (width, field) in widths.iter_mut().zip(row.iter()) {
+                    *width = (*width).max(field.chars().count());
+                }
+            }
+            let write_line = |writer: &mut W, fields: &mut dyn Iterator<Item = &str>| -> io::Result<()> {
+                let line: Vec<String> = fields
+                    .zip(widths.iter())
+                    .map(|(field, width)| format!("{:width$}", field, width = width))
+                    .collect();
+                writeln!(writer, "{}", line.join("  ").trim_end())
+            };
+            write_line(writer, &mut headers.iter().copied())?;
+            for row in rows {
+                write_line(writer, &mut row.iter().map(|field| field.as_ref()))?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use std::borrow::Cow;
+
+/// Quotes a CSV field if it contains a comma, quote, or newline
+pub fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_only_when_needed() {
+        assert_eq!(csv_field("Lobby AP"), "Lobby AP");
+        assert_eq!(csv_field("Branch, Two"), "\"Branch, Two\"");
+        assert_eq!(csv_field("Closet \"SW\""), "\"Closet \"\"SW\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -1,12 +1,22 @@
-use crate::{
-    gui::{
-        popup::{GuiError, PopupModal, ModalMeta},
-        {ChannelsGuiThread, ChannelsSearchThread},
+use crate::gui::{
+    popup::{GuiError, PopupModal, ModalMeta},
+    worker,
+    {
+        ChannelsGuiThread, ChannelsSearchThread, ControllerFields, ControllersFields, ProxyFields, SearchProgress,
+        SiteFilterFields,
+    },
+};
+use unifi_search_tool::{
+    mac_address::{MacAddress, MacCase, MacFormat, MacNotation},
+    unifi::{
+        devices::DeviceType,
+        cancel::CancellationToken,
+        controller::ControllerInfo,
+        profiles::ControllerProfiles,
+        search::{MacQuery, UnifiController, UnifiSearchInfo},
+        unknown_models::UnknownModels,
     },
-    mac_address::{MacAddress, validation::text_is_valid_mac},
-    unifi::search::{find_unifi_device, UnifiSearchInfo},
 };
-use std::thread;
 use zeroize::Zeroize;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,13 +33,26 @@ struct GuiInputFields {
     password_input: String,
     server_url_input: String,
     mac_addr_input: String,
+    nearby_range: u8,
+    device_type_filter: Option<DeviceType>,
+    search_clients_checked: bool,
+    all_sites_checked: bool,
     invalid_certs_checked: bool,
     remember_pass_checked: bool,
+    proxy_fields: ProxyFields,
+    /// Controllers from the controllers popup, the checked ones are searched along with the Server URL
+    saved_controllers: Vec<ControllerFields>,
 }
 
 pub(crate) struct GuiApp<'a> {
     font_size_enum: FontSize,
+    mac_format: MacFormat,
     gui_input_fields: GuiInputFields,
+    searched_mac_query: MacQuery,
+    /// From the last search, cleared when the server URL is changed
+    controller_info_option: Option<ControllerInfo>,
+    /// Set if the unknown models seen during the last search couldn't be saved
+    unknown_models_error_option: Option<Box<str>>,
     gui_channels: ChannelsGuiThread,
     popup_modal_option: Option<PopupModal<'a>>,
 }
@@ -40,7 +63,11 @@ impl eframe::App for GuiApp<'_> {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let Self {
             font_size_enum,
+            mac_format,
             gui_input_fields,
+            searched_mac_query,
+            controller_info_option,
+            unknown_models_error_option,
             gui_channels,
             popup_modal_option,
         } = self;
@@ -60,12 +87,21 @@ impl eframe::App for GuiApp<'_> {
             ui.shrink_width_to_current();
             ui.shrink_height_to_current();
 
-            GuiApp::create_menu_bar(ui, font_size_enum);
+            GuiApp::create_menu_bar(
+                ui,
+                font_size_enum,
+                mac_format,
+                gui_input_fields,
+                popup_modal_option,
+            );
             GuiApp::create_main_window(
                 ui,
                 gui_input_fields,
                 popup_modal_option,
-                &mut gui_channels.search_info_tx,
+                searched_mac_query,
+                controller_info_option,
+                unknown_models_error_option,
+                gui_channels,
             );
 
             let main_window_size: egui::Pos2 = {
@@ -77,7 +113,11 @@ impl eframe::App for GuiApp<'_> {
                 ctx,
                 popup_modal_option,
                 main_window_size,
-                &gui_input_fields.mac_addr_input,
+                gui_input_fields,
+                searched_mac_query,
+                controller_info_option,
+                unknown_models_error_option,
+                *mac_format,
                 gui_channels,
             );
 
@@ -95,44 +135,43 @@ impl Default for GuiApp<'_> {
 
         // create flume channels to communicate with the background thread
         let (search_info_tx, search_info_rx) = flume::bounded(1);
-        let (signal_tx, signal_rx) = flume::bounded(1);
-        let (percentage_tx, percentage_rx) = flume::bounded(1);
+        let (progress_tx, progress_rx) = flume::unbounded();
         let (device_tx, device_rx) = flume::bounded(1);
 
         // all of the channel pieces for the GUI thread
         let gui_channels = ChannelsGuiThread {
             search_info_tx,
-            signal_tx,
-            percentage_rx,
+            cancel_token: CancellationToken::new(),
+            progress_rx,
             device_rx,
         };
 
         // all of the channel pieces for the search thread
-        let mut search_thread_channels = ChannelsSearchThread {
+        let search_thread_channels = ChannelsSearchThread {
             search_info_rx,
-            signal_rx,
-            percentage_tx,
+            progress_tx,
             device_tx,
         };
 
         // spawn background thread to do the searching to avoid blocking the GUI thread
         // multiple flume channels used for communication between the gui thread and search thread
-        let _ = thread::spawn(move || loop {
-            let mut search_info = search_thread_channels.search_info_rx.recv()
-                .expect("receiving struct UnifiSearchInfo through channel search_info_rx should be successful");
-            let unifi_search_result =
-                find_unifi_device(&mut search_info, &mut search_thread_channels);
-            search_thread_channels
-                .device_tx
-                .send(unifi_search_result)
-                .expect(
-                    "sending unifi_search_result through channel device_tx should be successful",
-                );
-        });
+        worker::spawn_supervised_search_thread(search_thread_channels);
+
+        // the controllers popup shows the error if the profiles can't be read
+        let saved_controllers = ControllerProfiles::load()
+            .map(|controller_profiles| controller_profiles.controllers().map(ControllerFields::from_profile).collect())
+            .unwrap_or_default();
 
         Self {
             font_size_enum,
-            gui_input_fields: GuiInputFields::default(),
+            mac_format: MacFormat::default(),
+            gui_input_fields: GuiInputFields {
+                saved_controllers,
+                ..Default::default()
+            },
+            searched_mac_query: MacQuery::default(),
+            controller_info_option: None,
+            unknown_models_error_option: None,
             gui_channels,
             popup_modal_option: None,
         }
@@ -150,7 +189,14 @@ impl GuiApp<'_> {
         Default::default()
     }
 
-    fn create_menu_bar(ui: &mut egui::Ui, font_size_enum: &mut FontSize) {
+    fn create_menu_bar(
+        ui: &mut egui::Ui,
+        font_size_enum: &mut FontSize,
+        mac_format: &mut MacFormat,
+        gui_input_fields: &GuiInputFields,
+        popup_modal_option: &mut Option<PopupModal>,
+    ) {
+        let GuiInputFields { ref proxy_fields, ref server_url_input, ref saved_controllers, .. } = *gui_input_fields;
         // create top menu bar with light/dark buttons & hyperlinks
         egui::menu::bar(ui, |ui| {
             ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
@@ -164,6 +210,48 @@ impl GuiApp<'_> {
                         ui.selectable_value(font_size_enum, FontSize::Large, "Large");
                         ui.selectable_value(font_size_enum, FontSize::ExtraLarge, "Extra Large");
                     });
+                ui.label(" | ");
+                // each option is displayed as an example MAC Address in that format
+                let example_mac = MacAddress::new(0xAA_BB_CC_DD_EE_FF);
+                egui::ComboBox::from_id_salt("Menu Bar ComboBox - MAC Format")
+                    .selected_text("MAC Format")
+                    .show_ui(ui, |ui| {
+                        for case in MacCase::ALL {
+                            for notation in MacNotation::ALL {
+                                let option_format = MacFormat::new(notation, case);
+                                ui.selectable_value(
+                                    mac_format,
+                                    option_format,
+                                    example_mac.format(option_format).to_string(),
+                                );
+                            }
+                        }
+                    });
+                ui.label(" | ");
+                let unknown_models_tooltip: &'static str = "Device models seen during searches that this tool doesn't have a name for";
+                if ui.button("Unknown Models").on_hover_text(unknown_models_tooltip).clicked() {
+                    *popup_modal_option = match UnknownModels::load() {
+                        Ok(unknown_models) => Some(PopupModal::UnknownModels(unknown_models)),
+                        Err(e) => Some(PopupModal::Error(GuiError::new_standard(
+                            "Unknown Models",
+                            e.to_string().into_boxed_str(),
+                        ))),
+                    };
+                }
+                ui.label(" | ");
+                if ui.button("Proxy").on_hover_text(proxy_fields.summary()).clicked() {
+                    *popup_modal_option = Some(PopupModal::ProxySettings(proxy_fields.clone()));
+                }
+                ui.label(" | ");
+                let site_filter_tooltip: &'static str = "Choose which sites are searched, the filter is saved for the controller in the Server URL";
+                if ui.button("Site Filter").on_hover_text(site_filter_tooltip).clicked() {
+                    *popup_modal_option = Some(GuiApp::open_site_filter(server_url_input.trim()));
+                }
+                ui.label(" | ");
+                let controllers_tooltip: &'static str = "Save other controllers, each with its own login, & search them along with the Server URL";
+                if ui.button("Controllers").on_hover_text(controllers_tooltip).clicked() {
+                    *popup_modal_option = Some(GuiApp::open_controllers(saved_controllers));
+                }
             });
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 let source_code_url: &'static str = "https://github.com/Crypto-Spartan/unifi-search-tool";
@@ -183,19 +271,62 @@ impl GuiApp<'_> {
         });
     }
 
+    /// The site filter is saved per controller, so the server URL is needed to find it
+    fn open_site_filter<'a>(server_url: &str) -> PopupModal<'a> {
+        if server_url.is_empty() {
+            return PopupModal::Error(GuiError::new_standard(
+                "Site Filter",
+                Box::from("Enter the Server URL first, a site filter is saved for each controller."),
+            ));
+        }
+        match ControllerProfiles::load() {
+            Ok(controller_profiles) => PopupModal::SiteFilter(SiteFilterFields::new(
+                server_url,
+                &controller_profiles.site_filter(server_url),
+            )),
+            Err(e) => PopupModal::Error(GuiError::new_standard(
+                "Site Filter",
+                e.to_string().into_boxed_str(),
+            )),
+        }
+    }
+
+    /// The controllers in the popup are only kept once saved, so the saved list is edited as a copy
+    fn open_controllers<'a>(saved_controllers: &[ControllerFields]) -> PopupModal<'a> {
+        match ControllerProfiles::load() {
+            Ok(_) => PopupModal::Controllers(ControllersFields {
+                controllers: saved_controllers.to_vec(),
+                error_option: None,
+            }),
+            Err(e) => PopupModal::Error(GuiError::new_standard(
+                "Controllers",
+                e.to_string().into_boxed_str(),
+            )),
+        }
+    }
+
     fn create_main_window(
         ui: &mut egui::Ui,
         gui_input_fields: &mut GuiInputFields,
         popup_modal_option: &mut Option<PopupModal>,
-        search_info_tx: &mut flume::Sender<UnifiSearchInfo>,
+        searched_mac_query: &mut MacQuery,
+        controller_info_option: &mut Option<ControllerInfo>,
+        unknown_models_error_option: &mut Option<Box<str>>,
+        gui_channels: &mut ChannelsGuiThread,
     ) {
         let GuiInputFields {
             username_input,
             password_input,
             server_url_input,
             mac_addr_input,
+            nearby_range,
+            device_type_filter,
+            search_clients_checked,
+            all_sites_checked,
             invalid_certs_checked,
             remember_pass_checked,
+            proxy_fields: _,
+            saved_controllers,
         } = gui_input_fields;
 
         // title in main window
@@ -220,32 +351,95 @@ impl GuiApp<'_> {
                 ui.end_row();
 
                 ui.label("Server URL");
-                ui.add(
+                let server_url_response = ui.add(
                     egui::TextEdit::singleline(server_url_input)
                         .char_limit(2048)
                         .hint_text("https://<unifi-domain>:8443")
                         .desired_width(f32::INFINITY)
                 );
+                // the info belongs to the controller that was searched
+                if server_url_response.changed() {
+                    *controller_info_option = None;
+                }
                 ui.end_row();
 
+                if let Some(controller_info) = controller_info_option {
+                    ui.label("Controller");
+                    ui.label(controller_info.to_string());
+                    ui.end_row();
+                }
+
+                if let Some(unknown_models_error) = unknown_models_error_option {
+                    ui.label("Unknown Models");
+                    ui.colored_label(ui.visuals().error_fg_color, format!("Unable to save, {}", unknown_models_error));
+                    ui.end_row();
+                }
+
+                // saved controllers checked in the controllers popup
+                let also_search: Vec<&str> = saved_controllers
+                    .iter()
+                    .filter(|controller_fields| controller_fields.search_checked)
+                    .map(|controller_fields| controller_fields.name.trim())
+                    .collect();
+                if !also_search.is_empty() {
+                    ui.label("Also Search");
+                    ui.label(also_search.join(", "))
+                        .on_hover_text("Saved controllers checked in Controllers, each logs in with its own username & password");
+                    ui.end_row();
+                }
+
+                let mac_addr_tooltip: &'static str = "Full MAC Address: ff:ff:ff:ff:ff:ff, ff-ff-ff-ff-ff-ff, ff ff ff ff ff ff, ffff.ffff.ffff, or ffffffffffff\nPartial MAC Address: *:ff:ff:ff, ff:ff:ff:*, ??:??:??:ff:ff:f?, or the last 6+ digits like ffffff\nMAC Address range: ff:ff:ff:00:00:00/24\nSLAAC IPv6 address: fe80::fdff:ffff:feff:ffff";
                 ui.label("MAC Address");
                 ui.add(
                     egui::TextEdit::singleline(mac_addr_input)
-                        .char_limit(17)
+                        // longest input is an IPv6 address with a zone id or prefix length, leave room for surrounding whitespace when pasting
+                        .char_limit(64)
                         .hint_text("ff:ff:ff:ff:ff:ff")
                         .desired_width(f32::INFINITY)
-                );
+                ).on_hover_text(mac_addr_tooltip);
+                ui.end_row();
+
+                let nearby_range_tooltip: &'static str = "Also match devices whose MAC Address is within this many addresses of a full MAC Address. Unifi devices offset the MAC Address of their radios & ports from the management MAC Address, so the MAC Address seen on the wireless side is usually a few addresses away.";
+                ui.label("Nearby MACs");
+                ui.add(
+                    egui::DragValue::new(nearby_range)
+                        .range(0..=64)
+                        .prefix("± ")
+                ).on_hover_text(nearby_range_tooltip);
+                ui.end_row();
+
+                ui.label("Device Type");
+                egui::ComboBox::from_id_salt("Main Window ComboBox - Device Type")
+                    .selected_text(device_type_filter.as_ref().map_or("Any", |device_type| device_type.name()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(device_type_filter, None, "Any");
+                        for device_type in DeviceType::KNOWN {
+                            ui.selectable_value(device_type_filter, Some(device_type.clone()), device_type.name());
+                        }
+                    });
                 ui.end_row();
             });
 
         let remember_pass_tooltip: &'static str = "Checking this box prevents the password from being cleared between searches. The password will not be saved when the app is closed.";
         ui.checkbox(remember_pass_checked, "Remember Password").on_hover_text(remember_pass_tooltip);
+        let search_clients_tooltip: &'static str = "Also search the clients known to each site. Clients are skipped when a device type is selected.";
+        ui.checkbox(search_clients_checked, "Include Clients").on_hover_text(search_clients_tooltip);
+        // only disabled once a search has shown the account isn't a super admin
+        let can_list_all_sites = controller_info_option.as_ref().is_none_or(ControllerInfo::can_list_all_sites);
+        let all_sites_tooltip: &'static str = "Also search the sites this account isn't assigned to. Only super admins can see every site.";
+        ui.add_enabled(can_list_all_sites, egui::Checkbox::new(all_sites_checked, "Search All Sites"))
+            .on_hover_text(all_sites_tooltip)
+            .on_disabled_hover_text("Only super admins can search the sites they aren't assigned to, this account isn't a super admin.");
         ui.checkbox(invalid_certs_checked, "Accept Invalid HTTPS Certificate");
 
         // add "Search Unifi" button
         ui.vertical_centered(|ui| {
             if ui.button("Search Unifi").clicked() {
-                GuiApp::handle_button_click(gui_input_fields, popup_modal_option, search_info_tx);
+                GuiApp::handle_button_click(gui_input_fields, popup_modal_option, searched_mac_query, gui_channels);
+            }
+            let paste_text_tooltip: &'static str = "Paste ticket text, a switch MAC address table, or an ARP table & search for every MAC Address in it";
+            if ui.button("Search Pasted Text").on_hover_text(paste_text_tooltip).clicked() {
+                *popup_modal_option = Some(PopupModal::PasteText(String::new()));
             }
         });
     }
@@ -253,75 +447,177 @@ impl GuiApp<'_> {
     fn handle_button_click(
         gui_input_fields: &mut GuiInputFields,
         popup_modal_option: &mut Option<PopupModal>,
-        search_info_tx: &mut flume::Sender<UnifiSearchInfo>,
+        searched_mac_query: &mut MacQuery,
+        gui_channels: &mut ChannelsGuiThread,
+    ) {
+        let mac_addr_input = gui_input_fields.mac_addr_input.trim();
+        let mac_query_option = (!mac_addr_input.is_empty())
+            .then(|| MacQuery::parse(mac_addr_input, gui_input_fields.nearby_range));
+        GuiApp::start_search(
+            gui_input_fields,
+            popup_modal_option,
+            searched_mac_query,
+            gui_channels,
+            mac_query_option,
+        );
+    }
+
+    /// Validates the input fields & sends the search to the search thread.
+    /// `mac_query_option` is `None` if no MAC Address was entered
+    fn start_search(
+        gui_input_fields: &mut GuiInputFields,
+        popup_modal_option: &mut Option<PopupModal>,
+        searched_mac_query: &mut MacQuery,
+        gui_channels: &mut ChannelsGuiThread,
+        mac_query_option: Option<Result<MacQuery, Box<str>>>,
     ) {
         // all fields with `ref` are immutable when destructured
         let GuiInputFields {
             ref username_input,
             password_input,
             ref server_url_input,
-            ref mac_addr_input,
+            mac_addr_input: _,
+            nearby_range: _,
+            ref device_type_filter,
+            ref search_clients_checked,
+            ref all_sites_checked,
             ref invalid_certs_checked,
             ref remember_pass_checked,
+            ref proxy_fields,
+            saved_controllers,
         } = gui_input_fields;
 
-        // if any fields are empty, display error
-        if username_input.is_empty()
-        || password_input.is_empty()
-        || server_url_input.is_empty()
-        || mac_addr_input.is_empty() {
-            *popup_modal_option = Some(PopupModal::Error(
-                GuiError::new_standard(
-                    "Required Fields",
-                    Box::from("Username, Password, Server URL, & MAC Address are all required fields.")
-                )
-            ));
-        // if the mac address isn't in a valid format, display error
-        } else if !text_is_valid_mac(mac_addr_input.as_bytes()) {
-            *popup_modal_option = Some(PopupModal::Error(
-                GuiError::new_standard(
-                    "Invalid MAC Address",
-                    Box::from("MAC Address must be formatted like XX:XX:XX:XX:XX:XX or XX-XX-XX-XX-XX-XX with hexadecimal characters only.")
-                )
-            ));
+        // the Server URL can be left empty when saved controllers are searched
+        let search_saved_controllers = saved_controllers.iter().any(|controller_fields| controller_fields.search_checked);
+        let search_main_controller = !server_url_input.is_empty() || !search_saved_controllers;
+        let missing_required_fields = search_main_controller
+            && (username_input.is_empty() || password_input.is_empty() || server_url_input.is_empty());
+
+        let mac_query = match mac_query_option {
+            Some(Ok(mac_query)) if !missing_required_fields => mac_query,
+            // if the mac address isn't in a valid format, display error
+            Some(Err(error_desc)) if !missing_required_fields => {
+                *popup_modal_option = Some(PopupModal::Error(
+                    GuiError::new_standard(
+                        "Invalid MAC Address",
+                        error_desc,
+                    )
+                ));
+                return;
+            }
+            // if any fields are empty, display error
+            _ => {
+                *popup_modal_option = Some(PopupModal::Error(
+                    GuiError::new_standard(
+                        "Required Fields",
+                        Box::from("Username, Password, Server URL, & MAC Address are all required fields.")
+                    )
+                ));
+                return;
+            }
+        };
+
+        // passwords of saved controllers are only kept in memory, so they need to be entered after the app opens
+        let missing_login_option = saved_controllers.iter().find(|controller_fields| {
+            controller_fields.search_checked
+                && (controller_fields.username.is_empty() || controller_fields.password.is_empty())
+        });
+        if let Some(controller_fields) = missing_login_option {
+            *popup_modal_option = Some(PopupModal::Error(GuiError::new_standard(
+                "Required Fields",
+                format!(
+                    "Enter the username & password of {} in Controllers, or uncheck it.",
+                    controller_fields.name.trim()
+                ).into_boxed_str(),
+            )));
+            return;
+        }
+
+        // the site filter saved for each controller, if there is one
+        let controller_profiles = match ControllerProfiles::load() {
+            Ok(controller_profiles) => controller_profiles,
+            Err(e) => {
+                *popup_modal_option = Some(PopupModal::Error(GuiError::new_standard(
+                    "Site Filter",
+                    e.to_string().into_boxed_str(),
+                )));
+                return;
+            }
+        };
+
+        // don't zeroize the passwords if remember password checkbox is checked
+        // passwords are always zeroized on the search thread once the search finishes
+        let take_password = |password_input: &mut String| {
+            if *remember_pass_checked {
+                password_input.to_string()
+            } else {
+                let p = std::mem::take(password_input);
+                password_input.zeroize();
+                p
+            }
+        };
+        let mut controllers = Vec::new();
+        if search_main_controller {
+            controllers.push(UnifiController {
+                name: String::new(),
+                username: username_input.to_string(),
+                password: take_password(password_input),
+                server_url: server_url_input.to_string(),
+                accept_invalid_certs: *invalid_certs_checked,
+                site_filter: controller_profiles.site_filter(server_url_input),
+            });
+        }
+        for controller_fields in saved_controllers.iter_mut().filter(|controller_fields| controller_fields.search_checked) {
+            controllers.push(UnifiController {
+                name: controller_fields.name.trim().to_string(),
+                username: controller_fields.username.clone(),
+                password: take_password(&mut controller_fields.password),
+                server_url: controller_fields.server_url.clone(),
+                accept_invalid_certs: controller_fields.accept_invalid_certs,
+                site_filter: controller_profiles.site_filter(&controller_fields.server_url),
+            });
+        }
+        *searched_mac_query = mac_query.clone();
+        let search_info = UnifiSearchInfo {
+            controllers,
+            mac_query,
+            device_type_filter: device_type_filter.clone(),
+            search_clients: *search_clients_checked,
+            proxy: proxy_fields.to_proxy_mode(),
+            all_sites: *all_sites_checked,
+        };
+
         // other checks passed, run the search
-        } else {
-            *popup_modal_option = Some(PopupModal::SearchProgress(0.));
-
-            let username = username_input.to_string();
-            // don't zeroize the password if remember password checkbox is checked
-            // password is always zeroized on the search thread immediately after authentication
-            let password = {
-                if *remember_pass_checked {
-                    password_input.to_string()
-                } else {
-                    let p = std::mem::take(password_input);
-                    password_input.zeroize();
-                    p
-                }
-            };
-            let server_url = server_url_input.strip_suffix('/').unwrap_or(server_url_input).to_string();
-            let mac_to_search = MacAddress::try_from(mac_addr_input.as_ref())
-                .expect("Mac Address validation failed"); // SAFETY: this should never error due to the check above
-            let accept_invalid_certs = *invalid_certs_checked;
-
-            search_info_tx.send(
-                UnifiSearchInfo {
-                    username,
-                    password,
-                    server_url,
-                    mac_to_search,
-                    accept_invalid_certs
-                }
-            ).expect("sending struct UnifiSearchInfo through channel search_info_tx should be successful");
+        *popup_modal_option = Some(PopupModal::SearchProgress(SearchProgress::new(&search_info)));
+
+        // each search gets its own token, so canceling can't affect a later search
+        gui_channels.cancel_token = CancellationToken::new();
+        // discard progress left over from a canceled search
+        gui_channels.progress_rx.drain();
+        let send_result = gui_channels.search_info_tx.send((search_info, gui_channels.cancel_token.clone()));
+        // the search thread is only gone if it couldn't be respawned, which a restart may fix
+        if let Err(flume::SendError((mut search_info, _))) = send_result {
+            for controller in search_info.controllers.iter_mut() {
+                controller.password.zeroize();
+                controller.username.zeroize();
+            }
+            *popup_modal_option = Some(PopupModal::Error(GuiError::new_critical(
+                "Search Thread Stopped",
+                Box::from("The search thread isn't running, so the search can't be started.\nPlease restart the app."),
+            )));
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_popup_modal(
         ctx: &egui::Context,
         popup_modal_option: &mut Option<PopupModal>,
         main_window_size: egui::Pos2,
-        mac_addr_input: &str,
+        gui_input_fields: &mut GuiInputFields,
+        searched_mac_query: &mut MacQuery,
+        controller_info_option: &mut Option<ControllerInfo>,
+        unknown_models_error_option: &mut Option<Box<str>>,
+        mac_format: MacFormat,
         gui_channels: &mut ChannelsGuiThread,
     ) {
         if popup_modal_option.is_none() {
@@ -339,12 +635,15 @@ impl GuiApp<'_> {
         };
 
         match popup_modal {
-            PopupModal::SearchProgress(percentage) => {
+            PopupModal::SearchProgress(search_progress) => {
                 PopupModal::create_search_progress(
                     popup_metadata,
                     popup_modal_option,
-                    percentage,
-                    mac_addr_input,
+                    search_progress,
+                    searched_mac_query,
+                    gui_input_fields.search_clients_checked,
+                    controller_info_option,
+                    unknown_models_error_option,
                     gui_channels,
                 );
             }
@@ -353,16 +652,116 @@ impl GuiApp<'_> {
                     popup_metadata,
                     popup_modal_option,
                     unifi_device,
+                    searched_mac_query,
+                    mac_format,
+                );
+            }
+            PopupModal::SearchResults(search_matches) => {
+                PopupModal::create_search_results(
+                    popup_metadata,
+                    popup_modal_option,
+                    search_matches,
+                    searched_mac_query,
+                    mac_format,
                 );
             }
             PopupModal::Error(error) => {
                 PopupModal::create_error(popup_metadata, popup_modal_option, error);
             }
+            PopupModal::UnknownModels(unknown_models) => {
+                PopupModal::create_unknown_models(
+                    popup_metadata,
+                    popup_modal_option,
+                    unknown_models,
+                );
+            }
+            PopupModal::PasteText(pasted_text) => {
+                let macs_option = PopupModal::create_paste_text(
+                    popup_metadata,
+                    popup_modal_option,
+                    pasted_text,
+                    mac_format,
+                );
+                if let Some(macs) = macs_option {
+                    GuiApp::start_search(
+                        gui_input_fields,
+                        popup_modal_option,
+                        searched_mac_query,
+                        gui_channels,
+                        Some(Ok(MacQuery::from_list(macs))),
+                    );
+                }
+            }
+            PopupModal::ProxySettings(proxy_fields) => {
+                if let Some(saved_proxy_fields) = PopupModal::create_proxy_settings(
+                    popup_metadata,
+                    popup_modal_option,
+                    proxy_fields,
+                ) {
+                    gui_input_fields.proxy_fields = saved_proxy_fields;
+                }
+            }
+            PopupModal::SiteFilter(site_filter_fields) => {
+                if let Some((server_url, site_filter)) = PopupModal::create_site_filter(
+                    popup_metadata,
+                    popup_modal_option,
+                    site_filter_fields,
+                ) {
+                    let save_result = ControllerProfiles::load().and_then(|mut controller_profiles| {
+                        controller_profiles.get_or_insert(&server_url).site_filter = site_filter;
+                        controller_profiles.save()
+                    });
+                    if let Err(e) = save_result {
+                        *popup_modal_option = Some(PopupModal::Error(GuiError::new_standard(
+                            "Site Filter",
+                            e.to_string().into_boxed_str(),
+                        )));
+                    }
+                }
+            }
+            PopupModal::Controllers(controllers_fields) => {
+                if let Some(saved_controllers) = PopupModal::create_controllers(
+                    popup_metadata,
+                    popup_modal_option,
+                    controllers_fields,
+                ) {
+                    let save_result = ControllerProfiles::load().and_then(|mut controller_profiles| {
+                        let removed_names: Vec<Box<str>> = controller_profiles
+                            .controllers()
+                            .filter(|profile| {
+                                !saved_controllers.iter().any(|controller_fields| {
+                                    controller_fields.name.trim().eq_ignore_ascii_case(&profile.name)
+                                })
+                            })
+                            .map(|profile| profile.name.clone())
+                            .collect();
+                        for name in removed_names.iter() {
+                            controller_profiles.remove_controller(name);
+                        }
+                        for controller_fields in saved_controllers.iter() {
+                            let profile = controller_profiles.save_controller(&controller_fields.name, &controller_fields.server_url);
+                            profile.username = Some(controller_fields.username.trim())
+                                .filter(|username| !username.is_empty())
+                                .map(Box::from);
+                            profile.accept_invalid_certs = controller_fields.accept_invalid_certs;
+                        }
+                        controller_profiles.save()
+                    });
+                    gui_input_fields.saved_controllers = saved_controllers;
+                    if let Err(e) = save_result {
+                        *popup_modal_option = Some(PopupModal::Error(GuiError::new_standard(
+                            "Controllers",
+                            e.to_string().into_boxed_str(),
+                        )));
+                    }
+                }
+            }
             PopupModal::DisplayCancel => {
                 PopupModal::create_cancel(
                     popup_metadata,
                     popup_modal_option,
                     &mut gui_channels.device_rx,
+                    unknown_models_error_option,
                 );
             }
         }
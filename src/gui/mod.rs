@@ -1,22 +1,289 @@
 pub(crate) mod app;
 mod popup;
+mod worker;
 
-use crate::unifi::search::{UnifiSearchInfo, UnifiSearchResult};
+use unifi_search_tool::unifi::{
+    cancel::CancellationToken,
+    controller::ControllerInfo,
+    profiles::{ControllerProfile, ControllerProfiles},
+    progress::SearchEvent,
+    proxy::{ProxyConfig, ProxyMode},
+    search::{UnifiSearchInfo, UnifiSearchResult},
+    server_url::ServerUrl,
+    site_filter::{SiteFilter, SiteFilterError, SitePattern},
+};
 use flume::{Receiver, Sender};
 
-#[derive(Debug, Eq, PartialEq)]
-pub(crate) struct CancelSignal;
+/// Progress of the running search, shown in the search progress popup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SearchProgress {
+    /// Fraction of sites searched, from `0.0` to `1.0`
+    pub(crate) fraction: f32,
+    pub(crate) status: Box<str>,
+    pub(crate) match_count: usize,
+    /// The controller URL once it's known, shown so the port chosen by probing is visible
+    pub(crate) server_url: Option<Box<str>>,
+    /// Only set when a single controller is searched, so it can't be mistaken for another controller's info
+    pub(crate) controller_info: Option<ControllerInfo>,
+    /// Status of each controller, only when several controllers are searched
+    pub(crate) controllers: Vec<ControllerProgress>,
+    /// Index of the controller being searched
+    current_controller: usize,
+}
+
+/// Progress of a single controller when several controllers are searched
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ControllerProgress {
+    pub(crate) name: Box<str>,
+    pub(crate) status: Box<str>,
+    pub(crate) match_count: usize,
+}
+
+impl SearchProgress {
+    /// Lists every controller in the search as waiting, when there's more than one
+    pub(crate) fn new(search_info: &UnifiSearchInfo) -> Self {
+        let controllers = match search_info.controllers.len() {
+            0 | 1 => Vec::new(),
+            _ => search_info
+                .controllers
+                .iter()
+                .map(|controller| ControllerProgress {
+                    name: Box::from(controller.display_name()),
+                    status: Box::from("Waiting"),
+                    match_count: 0,
+                })
+                .collect(),
+        };
+        Self { controllers, ..Default::default() }
+    }
+
+    /// Sets the status of the search & of the controller being searched
+    fn set_status(&mut self, status: Box<str>) {
+        if let Some(controller_progress) = self.controllers.get_mut(self.current_controller) {
+            controller_progress.status.clone_from(&status);
+        }
+        self.status = status;
+    }
+
+    /// Updates the progress from an event, `sites_done` & `sites_total` are tracked by the search thread
+    fn update(&mut self, event: &SearchEvent, sites_done: &mut usize, sites_total: &mut usize) {
+        match event {
+            SearchEvent::ControllerStarted { name, index, count } => {
+                (*sites_done, *sites_total) = (0, 0);
+                self.current_controller = *index;
+                self.fraction = *index as f32 / (*count).max(1) as f32;
+                self.set_status(match count {
+                    1 => Box::from("Logging in"),
+                    _ => format!("Logging in to {}", name).into_boxed_str(),
+                });
+            }
+            SearchEvent::ServerUrlResolved { url, probed } => {
+                let url = url.as_str().trim_end_matches('/');
+                self.server_url = Some(match probed {
+                    true => format!("Controller: {} (port found automatically)", url).into_boxed_str(),
+                    false => format!("Controller: {}", url).into_boxed_str(),
+                });
+            }
+            SearchEvent::LoggedIn => self.set_status(Box::from("Logged in, getting sites")),
+            SearchEvent::ControllerIdentified(controller_info) => {
+                if self.controllers.is_empty() {
+                    self.controller_info = Some((*controller_info).clone());
+                }
+            }
+            SearchEvent::SitesListed(n) => *sites_total = *n,
+            SearchEvent::SiteStarted(site) => {
+                // each controller is an equal share of the progress bar
+                let controller_count = self.controllers.len().max(1) as f32;
+                let controller_fraction = *sites_done as f32 / (*sites_total).max(1) as f32;
+                self.fraction = (self.current_controller as f32 + controller_fraction) / controller_count;
+                self.set_status(
+                    format!("Searching {} ({} of {})", site.desc, *sites_done + 1, sites_total).into_boxed_str(),
+                );
+            }
+            SearchEvent::SiteFinished(..) => *sites_done += 1,
+            // recorded by the search thread, nothing is displayed
+            SearchEvent::UnknownModel { .. } => {}
+            SearchEvent::Retrying { site, attempt, .. } => {
+                self.set_status(
+                    format!("Network error searching {}, retrying (attempt {})", site.desc, attempt).into_boxed_str(),
+                );
+            }
+            SearchEvent::Match(_) => {
+                self.match_count += 1;
+                if let Some(controller_progress) = self.controllers.get_mut(self.current_controller) {
+                    controller_progress.match_count += 1;
+                }
+            }
+            SearchEvent::ControllerFinished { .. } => {
+                if let Some(controller_progress) = self.controllers.get_mut(self.current_controller) {
+                    controller_progress.status = Box::from("Done");
+                }
+            }
+            SearchEvent::ControllerFailed { error, .. } => {
+                if let Some(controller_progress) = self.controllers.get_mut(self.current_controller) {
+                    controller_progress.status = format!("Failed: {}", error).into_boxed_str();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum ProxyChoice {
+    #[default]
+    System,
+    Disabled,
+    Custom,
+}
+
+/// Proxy settings as entered in the proxy popup, the custom proxy fields are kept while another choice is selected
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ProxyFields {
+    pub(crate) choice: ProxyChoice,
+    pub(crate) url: String,
+    pub(crate) username: String,
+    pub(crate) password: String,
+}
+
+impl ProxyFields {
+    pub(crate) fn to_proxy_mode(&self) -> ProxyMode {
+        match self.choice {
+            ProxyChoice::System => ProxyMode::System,
+            ProxyChoice::Disabled => ProxyMode::Disabled,
+            ProxyChoice::Custom => ProxyMode::Custom(ProxyConfig {
+                url: self.url.trim().to_string(),
+                username: Some(self.username.clone()).filter(|username| !username.is_empty()),
+                password: Some(self.password.clone()).filter(|password| !password.is_empty()),
+            }),
+        }
+    }
+
+    /// Shown when hovering the proxy button
+    pub(crate) fn summary(&self) -> String {
+        match self.choice {
+            ProxyChoice::System => String::from("Proxy: from the HTTPS_PROXY & NO_PROXY environment variables"),
+            ProxyChoice::Disabled => String::from("Proxy: none, connecting directly"),
+            ProxyChoice::Custom => format!("Proxy: {}", self.url.trim()),
+        }
+    }
+}
+
+/// Site filter as entered in the site filter popup, one pattern per line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SiteFilterFields {
+    /// The filter is saved to the profile of this controller
+    pub(crate) server_url: Box<str>,
+    pub(crate) include: String,
+    pub(crate) exclude: String,
+    /// Shown when a pattern can't be parsed
+    pub(crate) error_option: Option<Box<str>>,
+}
+
+impl SiteFilterFields {
+    pub(crate) fn new(server_url: &str, site_filter: &SiteFilter) -> Self {
+        let join_patterns = |patterns: &[SitePattern]| {
+            patterns.iter().map(SitePattern::as_str).collect::<Vec<&str>>().join("\n")
+        };
+        Self {
+            server_url: Box::from(server_url),
+            include: join_patterns(&site_filter.include),
+            exclude: join_patterns(&site_filter.exclude),
+            error_option: None,
+        }
+    }
+
+    pub(crate) fn to_site_filter(&self) -> Result<SiteFilter, SiteFilterError> {
+        Ok(SiteFilter::new(
+            SitePattern::parse_lines(&self.include)?,
+            SitePattern::parse_lines(&self.exclude)?,
+        ))
+    }
+}
+
+/// A saved controller as entered in the controllers popup, the password is only kept while the app is open
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ControllerFields {
+    pub(crate) name: String,
+    pub(crate) server_url: String,
+    pub(crate) username: String,
+    pub(crate) password: String,
+    pub(crate) accept_invalid_certs: bool,
+    /// Searched along with the controller entered in the main window
+    pub(crate) search_checked: bool,
+}
+
+impl ControllerFields {
+    pub(crate) fn from_profile(profile: &ControllerProfile) -> Self {
+        Self {
+            name: profile.name.to_string(),
+            server_url: profile.server_url.to_string(),
+            username: profile.username.as_deref().unwrap_or_default().to_string(),
+            accept_invalid_certs: profile.accept_invalid_certs,
+            ..Default::default()
+        }
+    }
+}
+
+/// The saved controllers as edited in the controllers popup, only kept once saved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ControllersFields {
+    pub(crate) controllers: Vec<ControllerFields>,
+    /// Shown when a controller is missing its name or server URL
+    pub(crate) error_option: Option<Box<str>>,
+}
+
+impl ControllersFields {
+    /// Each controller needs a unique name & a valid server URL
+    pub(crate) fn validate(&self) -> Result<(), Box<str>> {
+        for (i, controller_fields) in self.controllers.iter().enumerate() {
+            let name = controller_fields.name.trim();
+            if name.is_empty() {
+                return Err(format!("Controller {} needs a name.", i + 1).into_boxed_str());
+            }
+            if controller_fields.server_url.trim().is_empty() {
+                return Err(format!("{} needs a Server URL.", name).into_boxed_str());
+            }
+            if let Err(e) = ServerUrl::parse(&controller_fields.server_url) {
+                return Err(format!("{}: {}", name, e).into_boxed_str());
+            }
+            let is_duplicate = self.controllers[..i]
+                .iter()
+                .any(|other| other.name.trim().eq_ignore_ascii_case(name));
+            if is_duplicate {
+                return Err(format!("{} is used by more than one controller.", name).into_boxed_str());
+            }
+            // a profile is saved for each server URL, so two controllers can't share one
+            let server_url_key = ControllerProfiles::profile_key(&controller_fields.server_url);
+            let is_duplicate_url = self.controllers[..i]
+                .iter()
+                .any(|other| ControllerProfiles::profile_key(&other.server_url) == server_url_key);
+            if is_duplicate_url {
+                return Err(format!("{} has the same Server URL as another controller.", name).into_boxed_str());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sent to the GUI thread once a search stops
+pub(crate) enum SearchOutcome {
+    /// With the error if the unknown models seen during the search couldn't be saved
+    Finished(UnifiSearchResult, Option<Box<str>>),
+    /// The search thread panicked with this message & was respawned
+    Panicked(Box<str>),
+}
 
 struct ChannelsGuiThread {
-    search_info_tx: Sender<UnifiSearchInfo>,
-    signal_tx: Sender<CancelSignal>,
-    percentage_rx: Receiver<f32>,
-    device_rx: Receiver<UnifiSearchResult>,
+    search_info_tx: Sender<(UnifiSearchInfo, CancellationToken)>,
+    /// Token for the running search, replaced each time a search starts
+    cancel_token: CancellationToken,
+    progress_rx: Receiver<SearchProgress>,
+    device_rx: Receiver<SearchOutcome>,
 }
 
+#[derive(Clone)]
 pub(crate) struct ChannelsSearchThread {
-    pub(crate) search_info_rx: Receiver<UnifiSearchInfo>,
-    pub(crate) signal_rx: Receiver<CancelSignal>,
-    pub(crate) percentage_tx: Sender<f32>,
-    pub(crate) device_tx: Sender<UnifiSearchResult>,
+    pub(crate) search_info_rx: Receiver<(UnifiSearchInfo, CancellationToken)>,
+    pub(crate) progress_tx: Sender<SearchProgress>,
+    pub(crate) device_tx: Sender<SearchOutcome>,
 }
@@ -1,9 +1,20 @@
-use crate::{
-    gui::{CancelSignal, ChannelsGuiThread},
-    unifi::{api::UnifiAPIError, devices::UnifiDeviceBasic, search::UnifiSearchResult},
+use crate::gui::{
+    ChannelsGuiThread, ControllerFields, ControllersFields, ProxyChoice, ProxyFields, SearchOutcome, SearchProgress,
+    SiteFilterFields,
+};
+use unifi_search_tool::{
+    mac_address::{extract_macs, MacAddress, MacFormat},
+    unifi::{
+        api::UnifiAPIError,
+        controller::ControllerInfo,
+        devices::UnifiDeviceBasic,
+        search::{FailedController, MacQuery, SiteCoverage, UnifiSearchMatches},
+        site_filter::SiteFilter,
+        unknown_models::UnknownModels,
+    },
 };
 use egui::{Id, TextBuffer};
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 #[derive(Debug, Clone, PartialEq)]
 pub(super) enum GuiErrorLevel {
@@ -45,9 +56,15 @@ impl GuiError<'_> {
 
 #[derive(Debug, Clone, PartialEq)]
 pub(super) enum PopupModal<'a> {
-    SearchProgress(f32),
+    SearchProgress(SearchProgress),
     SearchResult(UnifiDeviceBasic),
+    SearchResults(UnifiSearchMatches),
     Error(GuiError<'a>),
+    UnknownModels(UnknownModels),
+    PasteText(String),
+    ProxySettings(ProxyFields),
+    SiteFilter(SiteFilterFields),
+    Controllers(ControllersFields),
     DisplayCancel,
 }
 
@@ -59,31 +76,77 @@ pub(super) struct ModalMeta<'a> {
 }
 
 impl<'a> PopupModal<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn create_search_progress(
         popup_metadata: ModalMeta,
         popup_modal_option: &mut Option<PopupModal>,
-        mut percentage: f32,
-        mac_address: &str,
+        mut search_progress: SearchProgress,
+        mac_query: &MacQuery,
+        search_clients: bool,
+        controller_info_option: &mut Option<ControllerInfo>,
+        unknown_models_error: &mut Option<Box<str>>,
         gui_channels: &mut ChannelsGuiThread,
     ) {
-        // get percentage value from channel to update the progress bar
-        if let Ok(new_percentage) = gui_channels.percentage_rx.try_recv() {
-            *popup_modal_option = Some(PopupModal::SearchProgress(new_percentage));
-            percentage = new_percentage;
+        // only the latest progress from the channel is displayed
+        if let Some(new_search_progress) = gui_channels.progress_rx.try_iter().last() {
+            // kept after the search, so the main window can show what the account is allowed to do
+            if new_search_progress.controller_info.is_some() {
+                controller_info_option.clone_from(&new_search_progress.controller_info);
+            }
+            *popup_modal_option = Some(PopupModal::SearchProgress(new_search_progress.clone()));
+            search_progress = new_search_progress;
         }
 
         egui::Modal::new(Id::new("Search Progress Modal")).show(popup_metadata.ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.heading("Running Unifi Search");
 
-                ui.label(format!(
-                    "Searching for Unifi device with MAC Address: {}",
-                    mac_address
-                ));
+                match mac_query {
+                    MacQuery::List(macs) => ui.label(format!(
+                        "Searching for Unifi devices matching {} MAC Addresses",
+                        macs.len()
+                    )),
+                    _ => ui.label(format!(
+                        "Searching for Unifi device with MAC Address: {}",
+                        mac_query
+                    )),
+                };
+
+                if let Some(server_url) = &search_progress.server_url {
+                    ui.label(&**server_url);
+                }
+                if let Some(controller_info) = &search_progress.controller_info {
+                    ui.label(controller_info.to_string());
+                }
+                if !search_progress.status.is_empty() {
+                    ui.label(&*search_progress.status);
+                }
+                if search_progress.match_count > 0 && !matches!(mac_query, MacQuery::Exact(_)) {
+                    ui.label(format!("Matches found so far: {}", search_progress.match_count));
+                }
+
+                // each controller's status, when several controllers are searched
+                if !search_progress.controllers.is_empty() {
+                    egui::Grid::new("Search Progress Modal - Controllers Grid")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Controller");
+                            ui.strong("Status");
+                            ui.strong("Matches");
+                            ui.end_row();
+                            for controller_progress in search_progress.controllers.iter() {
+                                ui.label(&*controller_progress.name);
+                                ui.label(&*controller_progress.status);
+                                ui.label(controller_progress.match_count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                }
 
                 // create progress bar
                 let progress_bar = {
-                    egui::widgets::ProgressBar::new(percentage)
+                    egui::widgets::ProgressBar::new(search_progress.fraction)
                         .show_percentage()
                         .animate(true)
                 };
@@ -91,7 +154,7 @@ impl<'a> PopupModal<'a> {
 
                 // cancel button
                 if ui.button("Cancel").clicked() {
-                    gui_channels.signal_tx.send(CancelSignal).unwrap();
+                    gui_channels.cancel_token.cancel();
                     *popup_modal_option = Some(PopupModal::DisplayCancel);
                 }
             });
@@ -103,26 +166,47 @@ impl<'a> PopupModal<'a> {
         }
 
         // check channel to see if we have a search result
-        if let Ok(unifi_search_result) = gui_channels.device_rx.try_recv() {
+        if let Ok(mut unifi_search_result) = gui_channels.device_rx.try_recv() {
+            if let SearchOutcome::Finished(_, unknown_models_error_option) = &mut unifi_search_result {
+                *unknown_models_error = unknown_models_error_option.take();
+            }
             match unifi_search_result {
-                Ok(unifi_search_option) => match unifi_search_option {
-                    Some(unifi_device) => {
+                SearchOutcome::Finished(Ok(mut search_matches), _) => match (search_matches.devices.len(), search_matches.clients.len()) {
+                    // a single device gets the detailed view, anything else is displayed as a list.
+                    // controllers that failed are only listed in the results list
+                    (1, 0) if search_matches.failed_controllers.is_empty() => {
+                        let unifi_device = search_matches.devices.pop().expect("length was checked");
                         *popup_modal_option =
                             Some(PopupModal::SearchResult(unifi_device));
                     }
-                    None => {
+                    (0, 0) => {
                         *popup_modal_option =
                             Some(PopupModal::Error(GuiError::new_info(
                                 "Device Not Found",
-                                format!(
-                                    "Unable to find device with MAC Address {}",
-                                    mac_address
-                                )
+                                (match (mac_query, PopupModal::not_found_hint(mac_query, search_clients)) {
+                                    (MacQuery::List(macs), _) => format!(
+                                        "Unable to find a device with any of the {} MAC Addresses",
+                                        macs.len()
+                                    ),
+                                    (_, Some(hint)) => format!(
+                                        "Unable to find device with MAC Address {}\n{}",
+                                        mac_query, hint
+                                    ),
+                                    (_, None) => format!(
+                                        "Unable to find device with MAC Address {}",
+                                        mac_query
+                                    ),
+                                } + "\n" + &PopupModal::site_coverage_hint(search_matches.sites)
+                                    + &PopupModal::failed_controllers_hint(&search_matches.failed_controllers))
                                 .into_boxed_str(),
                             )));
                     }
+                    _ => {
+                        *popup_modal_option =
+                            Some(PopupModal::SearchResults(search_matches));
+                    }
                 },
-                Err(ref unifi_api_error) => {
+                SearchOutcome::Finished(Err(ref unifi_api_error), _) => {
                     *popup_modal_option = match unifi_api_error {
                         UnifiAPIError::ClientError { source } => {
                             debug_assert!(source.is_builder());
@@ -135,6 +219,28 @@ impl<'a> PopupModal<'a> {
                                 .into_boxed_str(),
                             )))
                         }
+                        UnifiAPIError::RuntimeError { source } => {
+                            Some(PopupModal::Error(GuiError::new_critical(
+                                "Async Runtime Error",
+                                format!(
+                                    "Unable to Build Unifi Client\n{}\n{}",
+                                    unifi_api_error, source
+                                )
+                                .into_boxed_str(),
+                            )))
+                        }
+                        UnifiAPIError::InvalidUrl { .. } => {
+                            Some(PopupModal::Error(GuiError::new_standard(
+                                "Invalid Server URL",
+                                unifi_api_error.to_string().into_boxed_str(),
+                            )))
+                        }
+                        UnifiAPIError::InvalidProxy { .. } => {
+                            Some(PopupModal::Error(GuiError::new_standard(
+                                "Invalid Proxy",
+                                unifi_api_error.to_string().into_boxed_str(),
+                            )))
+                        }
                         UnifiAPIError::LoginAuthenticationError { url } => {
                             Some(PopupModal::Error(GuiError::new_standard(
                                 "Login Failed",
@@ -154,16 +260,82 @@ impl<'a> PopupModal<'a> {
                                 format!("{}\n{}", unifi_api_error, source).into_boxed_str(),
                             )))
                         }
+                        // a canceled search returns no matches, this is only for completeness
+                        UnifiAPIError::Canceled => None,
                     }
                 }
+                SearchOutcome::Panicked(panic_desc) => {
+                    *popup_modal_option = Some(PopupModal::search_thread_panicked(panic_desc));
+                }
             }
         }
     }
 
+    /// The search thread has already been restarted, so another search can be run right away
+    fn search_thread_panicked(panic_desc: Box<str>) -> PopupModal<'a> {
+        PopupModal::Error(GuiError::new_critical(
+            "Search Thread Crashed",
+            format!(
+                "The search stopped unexpectedly & was restarted, you can search again.\n{}",
+                panic_desc
+            )
+            .into_boxed_str(),
+        ))
+    }
+
+    /// Explains whether every site in the controller was searched
+    fn site_coverage_hint(sites: SiteCoverage) -> String {
+        match sites.not_searched() {
+            Some(0) => format!("Searched {}.", sites),
+            Some(_) => format!(
+                "Searched {}, check Search All Sites to include the sites this account isn't assigned to.",
+                sites
+            ),
+            None => format!("Searched {}, this account may not have access to every site.", sites),
+        }
+    }
+
+    /// A line for each controller that couldn't be searched, empty if every controller was searched
+    fn failed_controllers_hint(failed_controllers: &[FailedController]) -> String {
+        failed_controllers
+            .iter()
+            .map(|failed_controller| format!("\nUnable to search {}: {}", failed_controller.name, failed_controller.error))
+            .collect()
+    }
+
+    /// Explains why a MAC Address might not have been found, based on the address type & vendor
+    fn not_found_hint(mac_query: &MacQuery, search_clients: bool) -> Option<String> {
+        // partial MAC Addresses & ranges don't have a single vendor
+        let (MacQuery::Exact(mac) | MacQuery::Nearby { base: mac, .. }) = *mac_query else {
+            return None;
+        };
+        if mac.is_multicast() {
+            return Some(String::from(
+                "This is a multicast MAC Address, it doesn't belong to a single device."
+            ));
+        }
+        if mac.is_randomized() {
+            return Some(String::from(
+                "This is a randomized private MAC Address. Phones & laptops use these in place of their real MAC Address, which is why the controller may not know about it."
+            ));
+        }
+        let vendor = mac.vendor()?;
+        if search_clients || vendor.starts_with("Ubiquiti") {
+            Some(format!("This MAC Address belongs to {}.", vendor))
+        } else {
+            Some(format!(
+                "This MAC Address belongs to {}, try a client search by checking Include Clients.",
+                vendor
+            ))
+        }
+    }
+
     pub(super) fn create_search_result(
         popup_metadata: ModalMeta,
         popup_modal_option: &mut Option<PopupModal>,
         unifi_device: UnifiDeviceBasic,
+        mac_query: &MacQuery,
+        mac_format: MacFormat,
     ) {
         let UnifiDeviceBasic {
             mac,
@@ -207,22 +379,48 @@ impl<'a> PopupModal<'a> {
                         PopupModal::create_search_result_row(
                             ui, "Device Type / Model:",
                             format!("{} / {}",
-                                device_type.to_uppercase(),
+                                device_type,
                                 device_model
                             ),
                         );
                     }
 
+                    // add the vendor the MAC Address is registered to, if it's in the OUI registry
+                    if let Some(vendor) = mac.vendor() {
+                        PopupModal::create_search_result_row(
+                            ui, "Vendor:", vendor,
+                        );
+                    }
+
+                    // add the controller the device is in, if several controllers were searched
+                    if !site.controller.is_empty() {
+                        PopupModal::create_search_result_row(
+                            ui, "Controller:", site.controller.as_ref(),
+                        );
+                    }
+
                     // add the name of the Unifi site
                     PopupModal::create_search_result_row(
-                        ui, "Unifi Site:", site.as_ref(),
+                        ui, "Unifi Site:", site.desc.as_ref(),
                     );
 
                     // add the MAC address of the device found
                     PopupModal::create_search_result_row(
-                        ui, "MAC Address:", format!("{mac}"),
+                        ui, "MAC Address:", mac.format(mac_format).to_string(),
                     );
 
+                    // add the SLAAC link-local address, to match the device up with IPv6 logs
+                    PopupModal::create_search_result_row(
+                        ui, "IPv6 Link-Local:", mac.ipv6_link_local().to_string(),
+                    );
+
+                    // add how far the MAC address is from the one searched for, if this was a nearby search
+                    if let Some(offset) = mac_query.offset_of(mac) {
+                        PopupModal::create_search_result_row(
+                            ui, "Offset From Searched MAC:", PopupModal::format_offset(offset),
+                        );
+                    }
+
                     // add device status; ie if the device is connected, offline, or unknown
                     PopupModal::create_search_result_row(
                         // custom state.as_str implementation
@@ -255,6 +453,164 @@ impl<'a> PopupModal<'a> {
         });
     }
 
+    pub(super) fn create_search_results(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        search_matches: UnifiSearchMatches,
+        mac_query: &MacQuery,
+        mac_format: MacFormat,
+    ) {
+        let UnifiSearchMatches { devices, clients, sites, failed_controllers } = search_matches;
+        // nearby searches show how far each MAC Address is from the one searched for
+        let show_offset = matches!(mac_query, MacQuery::Nearby { .. });
+        // the controller is only set when several controllers were searched
+        let show_controller = devices.iter().any(|device| !device.site.controller.is_empty())
+            || clients.iter().any(|client| !client.site.controller.is_empty());
+
+        // MAC Addresses from pasted text that didn't match any device or client
+        let not_found_macs: Vec<MacAddress> = match mac_query {
+            MacQuery::List(macs) => macs
+                .iter()
+                .copied()
+                .filter(|mac| {
+                    !devices.iter().any(|device| device.mac == *mac)
+                        && !clients.iter().any(|client| client.mac == *mac)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        egui::Modal::new(Id::new("Search Results Modal")).show(popup_metadata.ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading("Unifi Search Results");
+                ui.label(format!(
+                    "Found {} matching devices & {} matching clients",
+                    devices.len(),
+                    clients.len()
+                ));
+                ui.label(PopupModal::site_coverage_hint(sites));
+                for failed_controller in failed_controllers.iter() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("Unable to search {}: {}", failed_controller.name, failed_controller.error),
+                    );
+                }
+            });
+
+            egui::ScrollArea::vertical().max_height(popup_metadata.width * 0.5).show(ui, |ui| {
+                if !devices.is_empty() {
+                    ui.strong("Devices");
+                    egui::Grid::new("Search Results Modal - Devices Grid")
+                        .num_columns(5 + usize::from(show_offset) + usize::from(show_controller))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Device Name");
+                            ui.strong("MAC Address");
+                            if show_offset {
+                                ui.strong("Offset");
+                            }
+                            if show_controller {
+                                ui.strong("Controller");
+                            }
+                            ui.strong("Unifi Site");
+                            ui.strong("Device Status");
+                            ui.end_row();
+
+                            for unifi_device in devices.iter() {
+                                ui.label(unifi_device.name_option.as_deref().unwrap_or("-"));
+                                ui.label(unifi_device.mac.format(mac_format).to_string());
+                                if let Some(offset) = mac_query.offset_of(unifi_device.mac) {
+                                    ui.label(PopupModal::format_offset(offset));
+                                }
+                                if show_controller {
+                                    ui.label(unifi_device.site.controller.as_ref());
+                                }
+                                ui.label(unifi_device.site.desc.as_ref());
+                                ui.label(unifi_device.state.as_str());
+                                // show the full search result popup for this device
+                                if ui.button("Details").clicked() {
+                                    *popup_modal_option = Some(PopupModal::SearchResult(unifi_device.clone()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                if !clients.is_empty() {
+                    ui.strong("Clients");
+                    egui::Grid::new("Search Results Modal - Clients Grid")
+                        .num_columns(6 + usize::from(show_offset) + usize::from(show_controller))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Client Name");
+                            ui.strong("MAC Address");
+                            if show_offset {
+                                ui.strong("Offset");
+                            }
+                            ui.strong("Vendor");
+                            if show_controller {
+                                ui.strong("Controller");
+                            }
+                            ui.strong("Unifi Site");
+                            ui.strong("Last IP");
+                            ui.strong("Last Seen");
+                            ui.end_row();
+
+                            for client_device in clients.iter() {
+                                ui.label(client_device.display_name().unwrap_or("-"));
+                                ui.label(client_device.mac.format(mac_format).to_string());
+                                if let Some(offset) = mac_query.offset_of(client_device.mac) {
+                                    ui.label(PopupModal::format_offset(offset));
+                                }
+                                ui.label(PopupModal::client_vendor(client_device.mac));
+                                if show_controller {
+                                    ui.label(client_device.site.controller.as_ref());
+                                }
+                                ui.label(client_device.site.desc.as_ref());
+                                ui.label(client_device.last_ip.as_deref().unwrap_or("-"));
+                                ui.label(
+                                    client_device.last_seen
+                                        .map(|last_seen| last_seen.format("%Y-%m-%d %H:%M UTC").to_string())
+                                        .unwrap_or_else(|| String::from("-"))
+                                );
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                if !not_found_macs.is_empty() {
+                    ui.strong(format!("Not Found ({})", not_found_macs.len()));
+                    for mac in not_found_macs.iter() {
+                        ui.label(mac.format(mac_format).to_string());
+                    }
+                }
+            });
+
+            // close button
+            ui.vertical_centered(|ui| {
+                PopupModal::create_close_button(ui, popup_modal_option);
+            });
+        });
+    }
+
+    /// Formatted like `+2` or `-1`, an offset of 0 is the MAC Address that was searched for
+    #[inline]
+    fn format_offset(offset: i64) -> String {
+        match offset {
+            0 => String::from("0 (exact)"),
+            _ => format!("{:+}", offset),
+        }
+    }
+
+    #[inline]
+    fn client_vendor(mac: MacAddress) -> &'static str {
+        if mac.is_randomized() {
+            "Randomized"
+        } else {
+            mac.vendor().unwrap_or("-")
+        }
+    }
+
     #[inline]
     fn create_search_result_row(
         ui: &mut egui::Ui,
@@ -297,10 +653,346 @@ impl<'a> PopupModal<'a> {
         });
     }
 
+    pub(super) fn create_unknown_models(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        unknown_models: UnknownModels,
+    ) {
+        egui::Modal::new(Id::new("Unknown Models Modal")).show(popup_metadata.ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading("Unknown Device Models");
+            });
+
+            if unknown_models.is_empty() {
+                ui.label("No unknown device models have been seen yet.");
+            } else {
+                ui.label(format!(
+                    "Saved to {}",
+                    UnknownModels::file_path().display()
+                ));
+                egui::ScrollArea::vertical().max_height(popup_metadata.width * 0.5).show(ui, |ui| {
+                    egui::Grid::new("Unknown Models Modal - Grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Type");
+                            ui.strong("Model");
+                            ui.strong("First Seen Site");
+                            ui.strong("Count");
+                            ui.end_row();
+
+                            for unknown_model in unknown_models.models() {
+                                ui.label(unknown_model.device_type.as_ref());
+                                ui.label(unknown_model.device_model.as_ref());
+                                ui.label(unknown_model.first_seen_site.as_ref());
+                                ui.label(unknown_model.count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
+
+            ui.vertical_centered(|ui| {
+                ui.horizontal(|ui| {
+                    if !unknown_models.is_empty() && ui.button("Export CSV").clicked() {
+                        *popup_modal_option = match unknown_models.export_csv() {
+                            Ok(path) => Some(PopupModal::Error(GuiError::new_info(
+                                "Export Complete",
+                                format!("Unknown models exported to {}", path.display()).into_boxed_str(),
+                            ))),
+                            Err(e) => Some(PopupModal::Error(GuiError::new_standard(
+                                "Export Failed",
+                                e.to_string().into_boxed_str(),
+                            ))),
+                        };
+                    }
+                    PopupModal::create_close_button(ui, popup_modal_option);
+                });
+            });
+        });
+    }
+
+    /// Returns the MAC Addresses found in the pasted text once the search button is clicked
+    pub(super) fn create_paste_text(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        mut pasted_text: String,
+        mac_format: MacFormat,
+    ) -> Option<Vec<MacAddress>> {
+        let mut search_macs_option = None;
+        egui::Modal::new(Id::new("Paste Text Modal")).show(popup_metadata.ctx, |ui| {
+            ui.set_width(popup_metadata.width);
+            ui.vertical_centered(|ui| {
+                ui.heading("Search Pasted Text");
+                ui.label("Paste ticket text, a switch MAC address table, or an ARP table");
+            });
+
+            egui::ScrollArea::vertical().max_height(popup_metadata.width * 0.4).show(ui, |ui| {
+                let text_edit = egui::TextEdit::multiline(&mut pasted_text)
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(8)
+                    .code_editor();
+                if ui.add(text_edit).changed() {
+                    *popup_modal_option = Some(PopupModal::PasteText(pasted_text.clone()));
+                }
+            });
+
+            let macs = extract_macs(&pasted_text);
+            ui.label(format!("Found {} unique MAC Addresses", macs.len()));
+            if !macs.is_empty() {
+                let preview: Vec<String> = macs
+                    .iter()
+                    .take(5)
+                    .map(|mac| mac.format(mac_format).to_string())
+                    .collect();
+                let more = if macs.len() > preview.len() { ", ..." } else { "" };
+                ui.weak(format!("{}{}", preview.join(", "), more));
+            }
+
+            ui.vertical_centered(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!macs.is_empty(), egui::Button::new("Search")).clicked() {
+                        *popup_modal_option = None;
+                        search_macs_option = Some(macs);
+                    }
+                    PopupModal::create_close_button(ui, popup_modal_option);
+                });
+            });
+        });
+        search_macs_option
+    }
+
+    /// Returns the proxy settings once they're saved
+    pub(super) fn create_proxy_settings(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        mut proxy_fields: ProxyFields,
+    ) -> Option<ProxyFields> {
+        let mut saved_proxy_fields_option = None;
+        egui::Modal::new(Id::new("Proxy Settings Modal")).show(popup_metadata.ctx, |ui| {
+            ui.set_width(popup_metadata.width);
+            ui.vertical_centered(|ui| {
+                ui.heading("Proxy Settings");
+            });
+
+            let mut changed = false;
+            changed |= ui.radio_value(
+                &mut proxy_fields.choice,
+                ProxyChoice::System,
+                "Use the HTTPS_PROXY, HTTP_PROXY, & NO_PROXY environment variables",
+            ).changed();
+            changed |= ui.radio_value(&mut proxy_fields.choice, ProxyChoice::Disabled, "No proxy").changed();
+            changed |= ui.radio_value(&mut proxy_fields.choice, ProxyChoice::Custom, "Custom proxy").changed();
+
+            if proxy_fields.choice == ProxyChoice::Custom {
+                egui::Grid::new("Proxy Settings Grid").num_columns(2).show(ui, |ui| {
+                    let proxy_url_tooltip: &'static str = "http:// or https:// for an HTTP proxy, socks5:// for a SOCKS5 proxy, or socks5h:// to also resolve the controller's hostname through the proxy";
+                    ui.label("Proxy URL").on_hover_text(proxy_url_tooltip);
+                    changed |= ui.add(
+                        egui::TextEdit::singleline(&mut proxy_fields.url)
+                            .hint_text("socks5h://jump.example.com:1080")
+                    ).on_hover_text(proxy_url_tooltip).changed();
+                    ui.end_row();
+
+                    ui.label("Username");
+                    changed |= ui.add(
+                        egui::TextEdit::singleline(&mut proxy_fields.username).hint_text("optional")
+                    ).changed();
+                    ui.end_row();
+
+                    ui.label("Password");
+                    changed |= ui.add(
+                        egui::TextEdit::singleline(&mut proxy_fields.password).password(true).hint_text("optional")
+                    ).changed();
+                    ui.end_row();
+                });
+                ui.weak("Hosts in the NO_PROXY environment variable are still connected to directly");
+            }
+
+            if changed {
+                *popup_modal_option = Some(PopupModal::ProxySettings(proxy_fields.clone()));
+            }
+
+            let is_valid = proxy_fields.choice != ProxyChoice::Custom || !proxy_fields.url.trim().is_empty();
+            ui.vertical_centered(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(is_valid, egui::Button::new("Save")).clicked() {
+                        *popup_modal_option = None;
+                        saved_proxy_fields_option = Some(proxy_fields.clone());
+                    }
+                    PopupModal::create_close_button(ui, popup_modal_option);
+                });
+            });
+        });
+        saved_proxy_fields_option
+    }
+
+    /// Returns the filter once it's saved, the patterns are checked before the popup closes
+    pub(super) fn create_site_filter(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        mut site_filter_fields: SiteFilterFields,
+    ) -> Option<(Box<str>, SiteFilter)> {
+        let mut saved_site_filter_option = None;
+        egui::Modal::new(Id::new("Site Filter Modal")).show(popup_metadata.ctx, |ui| {
+            ui.set_width(popup_metadata.width);
+            ui.vertical_centered(|ui| {
+                ui.heading("Site Filter");
+                ui.label(format!("Saved for {}", site_filter_fields.server_url));
+            });
+            ui.weak("One pattern per line, matched against the site code or description & ignoring case. Use * & ? as wildcards, prefix with re: for a regex, & prefix with code: or desc: to only match one of them, ie desc:*Archive* or code:re:^cust-\\d+$");
+
+            let mut changed = false;
+            let include_tooltip: &'static str = "Only sites matching one of these are searched, every site is searched if it's empty";
+            ui.label("Include Sites").on_hover_text(include_tooltip);
+            changed |= ui.add(
+                egui::TextEdit::multiline(&mut site_filter_fields.include)
+                    .desired_rows(3)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("every site")
+            ).on_hover_text(include_tooltip).changed();
+            let exclude_tooltip: &'static str = "Sites matching any of these are never searched";
+            ui.label("Exclude Sites").on_hover_text(exclude_tooltip);
+            changed |= ui.add(
+                egui::TextEdit::multiline(&mut site_filter_fields.exclude)
+                    .desired_rows(3)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("lab-*")
+            ).on_hover_text(exclude_tooltip).changed();
+
+            if let Some(error) = &site_filter_fields.error_option {
+                ui.colored_label(ui.visuals().error_fg_color, &**error);
+            }
+
+            ui.vertical_centered(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        match site_filter_fields.to_site_filter() {
+                            Ok(site_filter) => {
+                                saved_site_filter_option = Some((site_filter_fields.server_url.clone(), site_filter));
+                            }
+                            Err(e) => {
+                                site_filter_fields.error_option = Some(e.to_string().into_boxed_str());
+                                changed = true;
+                            }
+                        }
+                    }
+                    PopupModal::create_close_button(ui, popup_modal_option);
+                });
+            });
+
+            if saved_site_filter_option.is_some() {
+                *popup_modal_option = None;
+            } else if changed && popup_modal_option.is_some() {
+                *popup_modal_option = Some(PopupModal::SiteFilter(site_filter_fields.clone()));
+            }
+        });
+        saved_site_filter_option
+    }
+
+    /// Returns the controllers once they're saved, each is checked before the popup closes
+    pub(super) fn create_controllers(
+        popup_metadata: ModalMeta,
+        popup_modal_option: &mut Option<PopupModal>,
+        mut controllers_fields: ControllersFields,
+    ) -> Option<Vec<ControllerFields>> {
+        let mut saved_controllers_option = None;
+        egui::Modal::new(Id::new("Controllers Modal")).show(popup_metadata.ctx, |ui| {
+            ui.set_width(popup_metadata.width);
+            ui.vertical_centered(|ui| {
+                ui.heading("Controllers");
+            });
+            ui.weak("Checked controllers are searched along with the Server URL in the main window, which can be left empty. Passwords are never saved, they're only kept until the app is closed.");
+
+            let mut changed = false;
+            let mut remove_index_option = None;
+            egui::ScrollArea::vertical().max_height(popup_metadata.width * 0.5).show(ui, |ui| {
+                egui::Grid::new("Controllers Modal - Grid")
+                    .num_columns(7)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Search");
+                        ui.strong("Name");
+                        ui.strong("Server URL");
+                        ui.strong("Username");
+                        ui.strong("Password");
+                        ui.strong("Invalid Certs").on_hover_text("Accept Invalid HTTPS Certificate");
+                        ui.end_row();
+
+                        for (i, controller_fields) in controllers_fields.controllers.iter_mut().enumerate() {
+                            changed |= ui.checkbox(&mut controller_fields.search_checked, "").changed();
+                            changed |= ui.add(
+                                egui::TextEdit::singleline(&mut controller_fields.name)
+                                    .hint_text("emea")
+                                    .desired_width(80.)
+                            ).changed();
+                            changed |= ui.add(
+                                egui::TextEdit::singleline(&mut controller_fields.server_url)
+                                    .char_limit(2048)
+                                    .hint_text("https://<unifi-domain>:8443")
+                            ).changed();
+                            changed |= ui.add(
+                                egui::TextEdit::singleline(&mut controller_fields.username)
+                                    .desired_width(80.)
+                            ).changed();
+                            changed |= ui.add(
+                                egui::TextEdit::singleline(&mut controller_fields.password)
+                                    .password(true)
+                                    .desired_width(80.)
+                            ).changed();
+                            changed |= ui.checkbox(&mut controller_fields.accept_invalid_certs, "").changed();
+                            if ui.button("Remove").clicked() {
+                                remove_index_option = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+            if let Some(i) = remove_index_option {
+                controllers_fields.controllers.remove(i);
+                changed = true;
+            }
+            if ui.button("Add Controller").clicked() {
+                controllers_fields.controllers.push(ControllerFields {
+                    search_checked: true,
+                    ..Default::default()
+                });
+                changed = true;
+            }
+
+            if let Some(error) = &controllers_fields.error_option {
+                ui.colored_label(ui.visuals().error_fg_color, &**error);
+            }
+
+            ui.vertical_centered(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        match controllers_fields.validate() {
+                            Ok(()) => saved_controllers_option = Some(controllers_fields.controllers.clone()),
+                            Err(e) => {
+                                controllers_fields.error_option = Some(e);
+                                changed = true;
+                            }
+                        }
+                    }
+                    PopupModal::create_close_button(ui, popup_modal_option);
+                });
+            });
+
+            if saved_controllers_option.is_some() {
+                *popup_modal_option = None;
+            } else if changed && popup_modal_option.is_some() {
+                *popup_modal_option = Some(PopupModal::Controllers(controllers_fields.clone()));
+            }
+        });
+        saved_controllers_option
+    }
+
     pub(super) fn create_cancel(
         popup_metadata: ModalMeta,
         popup_modal_option: &mut Option<PopupModal>,
-        device_rx: &mut flume::Receiver<UnifiSearchResult>,
+        device_rx: &mut flume::Receiver<SearchOutcome>,
+        unknown_models_error: &mut Option<Box<str>>,
     ) {
         egui::Modal::new(Id::new("Cancel Modal")).show(popup_metadata.ctx, |ui| {
             ui.vertical_centered(|ui| {
@@ -309,8 +1001,18 @@ impl<'a> PopupModal<'a> {
             });
         });
 
-        if let Ok(Ok(None)) = device_rx.recv() {
-            *popup_modal_option = None;
+        // the search thread always sends a result once it stops, even if it found a device before seeing the cancellation.
+        // the channel is polled so the window stays responsive, the in-flight request is abandoned almost immediately
+        if let Ok(search_outcome) = device_rx.try_recv() {
+            *popup_modal_option = match search_outcome {
+                SearchOutcome::Finished(_, unknown_models_error_option) => {
+                    *unknown_models_error = unknown_models_error_option;
+                    None
+                }
+                SearchOutcome::Panicked(panic_desc) => Some(PopupModal::search_thread_panicked(panic_desc)),
+            };
+        } else {
+            popup_metadata.ctx.request_repaint_after(Duration::from_millis(50));
         }
     }
 
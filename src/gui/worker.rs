@@ -0,0 +1,107 @@
+use crate::gui::{ChannelsSearchThread, SearchOutcome, SearchProgress};
+use unifi_search_tool::unifi::{progress::SearchEvent, search::find_unifi_device, unknown_models::UnknownModels};
+use std::{
+    any::Any,
+    panic,
+    sync::Mutex,
+    thread,
+};
+
+const SEARCH_THREAD_NAME: &str = "search";
+
+/// Panic message & location from the search thread's panic hook, taken by the supervisor once the thread exits
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Spawns the search thread under a supervisor thread.
+/// If the search thread panics, the GUI is sent `SearchOutcome::Panicked` & a new search thread is spawned.
+/// Both threads exit once the GUI thread drops its channels
+pub(super) fn spawn_supervised_search_thread(search_thread_channels: ChannelsSearchThread) {
+    install_panic_hook();
+    let _ = thread::Builder::new()
+        .name(String::from("search supervisor"))
+        .spawn(move || loop {
+            let worker_channels = search_thread_channels.clone();
+            let worker = thread::Builder::new()
+                .name(String::from(SEARCH_THREAD_NAME))
+                .spawn(move || search_thread_loop(worker_channels));
+            let Ok(worker) = worker else {
+                let _ = search_thread_channels.device_tx.send(SearchOutcome::Panicked(Box::from(
+                    "Unable to spawn the search thread",
+                )));
+                break;
+            };
+            match worker.join() {
+                // the GUI thread is gone
+                Ok(()) => break,
+                Err(panic_payload) => {
+                    let panic_desc = LAST_PANIC
+                        .lock()
+                        .ok()
+                        .and_then(|mut last_panic| last_panic.take())
+                        .unwrap_or_else(|| panic_payload_message(&*panic_payload));
+                    // the next iteration respawns the search thread, any search sent in the meantime waits in the channel
+                    if search_thread_channels.device_tx.send(SearchOutcome::Panicked(panic_desc.into_boxed_str())).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+}
+
+/// Runs searches until the GUI thread drops its channels
+fn search_thread_loop(search_thread_channels: ChannelsSearchThread) {
+    let ChannelsSearchThread { search_info_rx, progress_tx, device_tx } = search_thread_channels;
+    while let Ok((mut search_info, cancel_token)) = search_info_rx.recv() {
+        let mut search_progress = SearchProgress::new(&search_info);
+        let (mut sites_done, mut sites_total) = (0, 0);
+        // if the existing list can't be read, nothing is recorded so the file isn't overwritten
+        let mut unknown_models_result = UnknownModels::load();
+        let unifi_search_result = find_unifi_device(
+            &mut search_info,
+            |event: &SearchEvent| {
+                if let SearchEvent::UnknownModel { .. } = event {
+                    if let Ok(unknown_models) = unknown_models_result.as_mut() {
+                        unknown_models.record_event(event);
+                    }
+                    return;
+                }
+                // send the progress of the search to GUI thread
+                search_progress.update(event, &mut sites_done, &mut sites_total);
+                let _ = progress_tx.send(search_progress.clone());
+            },
+            &cancel_token,
+        );
+        let unknown_models_saved = unknown_models_result.and_then(|unknown_models| match unknown_models.is_empty() {
+            true => Ok(()),
+            false => unknown_models.save(),
+        });
+        let unknown_models_error_option = unknown_models_saved.err().map(|e| e.to_string().into_boxed_str());
+        if device_tx.send(SearchOutcome::Finished(unifi_search_result, unknown_models_error_option)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Records the message & location of panics on the search thread, since the payload only has the message.
+/// Panics are still printed by the default hook
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        if thread::current().name() == Some(SEARCH_THREAD_NAME) {
+            if let Ok(mut last_panic) = LAST_PANIC.lock() {
+                *last_panic = Some(panic_info.to_string());
+            }
+        }
+        default_hook(panic_info);
+    }));
+}
+
+fn panic_payload_message(panic_payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
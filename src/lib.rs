@@ -0,0 +1,29 @@
+//! Search for Unifi devices & clients by MAC Address across every site in a Unifi controller.
+//!
+//! The library has no GUI dependencies, the desktop app & command line interface are built on top of it.
+//!
+//! ```no_run
+//! use unifi_search_tool::unifi::search::{find_unifi_device_headless, MacQuery, UnifiController, UnifiSearchInfo};
+//!
+//! let mut search_info = UnifiSearchInfo {
+//!     controllers: vec![UnifiController {
+//!         username: String::from("admin"),
+//!         password: String::from("password"),
+//!         server_url: String::from("https://unifi.example.com:8443"),
+//!         ..Default::default()
+//!     }],
+//!     mac_query: MacQuery::parse("74:83:c2:3f:a2:10", 0).unwrap(),
+//!     ..Default::default()
+//! };
+//! let search_matches = find_unifi_device_headless(&mut search_info).unwrap();
+//! for unifi_device in search_matches.devices {
+//!     println!("{} is in site {}", unifi_device.mac, unifi_device.site.desc);
+//! }
+//! ```
+
+/// Quoting fields for the CSV files & output
+pub mod csv;
+/// Parsing, formatting, & matching MAC Addresses
+pub mod mac_address;
+/// Unifi controller API client, device models, & searching
+pub mod unifi;
@@ -0,0 +1,58 @@
+use super::MacAddress;
+
+/// Unifi devices derive the MAC Addresses of their ports & radios by offsetting the base MAC Address,
+/// ie the 2.4GHz radio is base+1 & the 5GHz radio is base+2.
+/// All arithmetic is checked against the 48 bit range of a MAC Address.
+impl MacAddress {
+    /// `FF:FF:FF:FF:FF:FF`
+    pub const MAX: MacAddress = MacAddress(0xFFFFFF_FFFFFF);
+
+    /// Returns `None` if the result would be past `FF:FF:FF:FF:FF:FF`
+    #[inline]
+    pub fn checked_add(self, offset: u64) -> Option<MacAddress> {
+        self.0
+            .checked_add(offset)
+            .filter(|&n| n <= Self::MAX.0)
+            .map(MacAddress)
+    }
+
+    /// Returns `None` if the result would be before `00:00:00:00:00:00`
+    #[inline]
+    pub fn checked_sub(self, offset: u64) -> Option<MacAddress> {
+        self.0.checked_sub(offset).map(MacAddress)
+    }
+
+    /// Adds a positive or negative offset
+    #[inline]
+    pub fn checked_add_signed(self, offset: i64) -> Option<MacAddress> {
+        match offset.is_negative() {
+            true => self.checked_sub(offset.unsigned_abs()),
+            false => self.checked_add(offset.unsigned_abs()),
+        }
+    }
+
+    /// The next MAC Address, `None` after `FF:FF:FF:FF:FF:FF`
+    #[inline]
+    pub fn next(self) -> Option<MacAddress> {
+        self.checked_add(1)
+    }
+
+    /// The previous MAC Address, `None` before `00:00:00:00:00:00`
+    #[inline]
+    pub fn prev(self) -> Option<MacAddress> {
+        self.checked_sub(1)
+    }
+
+    /// Number of addresses between the two MAC Addresses, regardless of which is larger
+    #[inline]
+    pub fn distance(self, other: MacAddress) -> u64 {
+        self.0.abs_diff(other.0)
+    }
+
+    /// Signed offset of `self` from `base`, ie base+2 returns 2
+    #[inline]
+    pub fn offset_from(self, base: MacAddress) -> i64 {
+        // both values are at most 48 bits, so neither the casts nor the subtraction can overflow
+        self.0 as i64 - base.0 as i64
+    }
+}
@@ -0,0 +1,105 @@
+use super::MacAddress;
+use std::net::Ipv6Addr;
+
+/// Inserted between the OUI & the rest of the MAC Address to make an EUI-64
+const EUI64_FILLER: u64 = 0xFFFE;
+/// The U/L bit of the first byte, inverted in a modified EUI-64
+const UNIVERSAL_LOCAL_BIT: u64 = 0x02 << 56;
+
+impl MacAddress {
+    /// `AA:BB:CC:DD:EE:FF` becomes `AA:BB:CC:FF:FE:DD:EE:FF`
+    #[inline]
+    pub fn to_eui64(self) -> u64 {
+        let oui = self.0 >> 24;
+        let nic = self.0 & 0xFFFFFF;
+        (oui << 40) | (EUI64_FILLER << 24) | nic
+    }
+
+    /// EUI-64 with the U/L bit inverted, used as the interface identifier of SLAAC IPv6 addresses (RFC 4291)
+    #[inline]
+    pub fn to_modified_eui64(self) -> u64 {
+        self.to_eui64() ^ UNIVERSAL_LOCAL_BIT
+    }
+
+    /// Reverses `to_modified_eui64`, returns `None` if the interface identifier wasn't derived from a MAC Address
+    #[inline]
+    pub fn from_modified_eui64(interface_id: u64) -> Option<MacAddress> {
+        let eui64 = interface_id ^ UNIVERSAL_LOCAL_BIT;
+        if (eui64 >> 24) & 0xFFFF != EUI64_FILLER {
+            return None;
+        }
+        let oui = eui64 >> 40;
+        let nic = eui64 & 0xFFFFFF;
+        Some(MacAddress((oui << 24) | nic))
+    }
+
+    /// The `fe80::/64` address the device assigns itself with SLAAC
+    #[inline]
+    pub fn ipv6_link_local(self) -> Ipv6Addr {
+        let prefix = 0xFE80_u128 << 112;
+        Ipv6Addr::from(prefix | u128::from(self.to_modified_eui64()))
+    }
+
+    /// Extracts the MAC Address from the interface identifier of a SLAAC IPv6 address, link-local or global.
+    /// Returns `None` for privacy extension or DHCPv6 addresses, which aren't derived from a MAC Address
+    #[inline]
+    pub fn from_ipv6(addr: Ipv6Addr) -> Option<MacAddress> {
+        // the lower 64 bits are the interface identifier, truncation is intentional
+        Self::from_modified_eui64(u128::from(addr) as u64)
+    }
+}
+
+/// Parses an IPv6 address copied from logs or `ip addr`, ignoring a zone id (`%eth0`) or prefix length (`/64`)
+pub fn parse_ipv6(input: &str) -> Option<Ipv6Addr> {
+    let addr_str = input
+        .split(['%', '/'])
+        .next()
+        .unwrap_or(input)
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    addr_str.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(s: &str) -> MacAddress {
+        MacAddress::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn inserts_fffe_and_flips_the_universal_local_bit() {
+        let mac = mac("74:83:c2:3f:a2:10");
+        assert_eq!(mac.to_eui64(), 0x7483_C2FF_FE3F_A210);
+        assert_eq!(mac.to_modified_eui64(), 0x7683_C2FF_FE3F_A210);
+        assert_eq!(mac.ipv6_link_local(), "fe80::7683:c2ff:fe3f:a210".parse::<Ipv6Addr>().unwrap());
+    }
+
+    #[test]
+    fn round_trips_through_ipv6() {
+        for mac in [mac("74:83:c2:3f:a2:10"), mac("00:00:00:00:00:00"), mac("ff:ff:ff:ff:ff:ff"), mac("02:00:5e:10:00:01")] {
+            assert_eq!(MacAddress::from_ipv6(mac.ipv6_link_local()), Some(mac));
+            assert_eq!(MacAddress::from_modified_eui64(mac.to_modified_eui64()), Some(mac));
+        }
+        // a global SLAAC address has the same interface identifier
+        let global: Ipv6Addr = "2001:db8:1:2:7683:c2ff:fe3f:a210".parse().unwrap();
+        assert_eq!(MacAddress::from_ipv6(global), Some(mac("74:83:c2:3f:a2:10")));
+    }
+
+    #[test]
+    fn ignores_addresses_not_derived_from_a_mac() {
+        assert_eq!(MacAddress::from_ipv6("fe80::1".parse().unwrap()), None);
+        assert_eq!(MacAddress::from_ipv6("2001:db8::a1b2:c3d4:e5f6:789".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn parses_ipv6_copied_from_logs() {
+        let expected: Ipv6Addr = "fe80::7683:c2ff:fe3f:a210".parse().unwrap();
+        for input in ["fe80::7683:c2ff:fe3f:a210", "fe80::7683:c2ff:fe3f:a210%eth0", "fe80::7683:c2ff:fe3f:a210/64", "[fe80::7683:c2ff:fe3f:a210]"] {
+            assert_eq!(parse_ipv6(input), Some(expected), "{input}");
+        }
+        assert_eq!(parse_ipv6("74:83:c2:3f:a2:10"), None);
+        assert_eq!(parse_ipv6("fe80"), None);
+    }
+}
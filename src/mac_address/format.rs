@@ -0,0 +1,148 @@
+use super::MacAddress;
+use serde::Serialize;
+
+/// How the hexadecimal digits of a MAC Address are grouped & separated
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MacNotation {
+    /// `XX:XX:XX:XX:XX:XX`
+    #[default]
+    Colon,
+    /// `XX-XX-XX-XX-XX-XX`
+    Hyphen,
+    /// `XX XX XX XX XX XX`
+    Space,
+    /// `XXXX.XXXX.XXXX`, used by Cisco
+    Dot,
+    /// `XXXXXXXXXXXX`
+    Bare,
+}
+
+impl MacNotation {
+    /// Every notation, in the order they're displayed
+    pub const ALL: [MacNotation; 5] = [
+        MacNotation::Colon,
+        MacNotation::Hyphen,
+        MacNotation::Space,
+        MacNotation::Dot,
+        MacNotation::Bare,
+    ];
+
+    /// Separator & the number of hex digits between each separator
+    #[inline]
+    const fn separator(self) -> Option<(u8, usize)> {
+        match self {
+            MacNotation::Colon  => Some((b':', 2)),
+            MacNotation::Hyphen => Some((b'-', 2)),
+            MacNotation::Space  => Some((b' ', 2)),
+            MacNotation::Dot    => Some((b'.', 4)),
+            MacNotation::Bare   => None,
+        }
+    }
+}
+
+/// Case of the hexadecimal digits
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MacCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+impl MacCase {
+    /// Every case, in the order they're displayed
+    pub const ALL: [MacCase; 2] = [MacCase::Upper, MacCase::Lower];
+
+    #[inline]
+    const fn hex_digits(self) -> &'static [u8; 16] {
+        match self {
+            MacCase::Upper => b"0123456789ABCDEF",
+            MacCase::Lower => b"0123456789abcdef",
+        }
+    }
+}
+
+/// Output format of a MAC Address, the default is uppercase & colon separated
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MacFormat {
+    pub notation: MacNotation,
+    pub case: MacCase,
+}
+
+impl MacFormat {
+    /// Longest formatted MAC Address, `XX:XX:XX:XX:XX:XX`
+    const MAX_LEN: usize = 17;
+
+    #[inline]
+    pub const fn new(notation: MacNotation, case: MacCase) -> Self {
+        Self { notation, case }
+    }
+
+    /// Writes the MAC Address into `buf`, returning the number of bytes written
+    fn write_to(self, mac: MacAddress, buf: &mut [u8; Self::MAX_LEN]) -> usize {
+        let hex_digits = self.case.hex_digits();
+        let separator = self.notation.separator();
+        let mut len = 0;
+        for nibble_idx in 0..12 {
+            if let Some((sep, group_len)) = separator {
+                if nibble_idx != 0 && nibble_idx % group_len == 0 {
+                    buf[len] = sep;
+                    len += 1;
+                }
+            }
+            let nibble = (mac.0 >> (44 - nibble_idx * 4)) & 0xF;
+            buf[len] = hex_digits[nibble as usize];
+            len += 1;
+        }
+        len
+    }
+}
+
+/// A MAC Address paired with the format it should be displayed or serialized with,
+/// created with `MacAddress::format`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormattedMac {
+    mac: MacAddress,
+    mac_format: MacFormat,
+}
+
+impl MacAddress {
+    /// Displays or serializes the MAC Address in the given format, ie `mac.format(mac_format).to_string()`
+    #[inline]
+    pub fn format(self, mac_format: MacFormat) -> FormattedMac {
+        FormattedMac { mac: self, mac_format }
+    }
+}
+
+impl std::fmt::Display for FormattedMac {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut buf = [0u8; MacFormat::MAX_LEN];
+        let len = self.mac_format.write_to(self.mac, &mut buf);
+        // SAFETY: only ascii hex digits & separators are written to the buffer
+        let mac_str = std::str::from_utf8(&buf[..len]).expect("formatted MAC Address should be ascii");
+        // `pad` so that width & alignment flags are respected
+        f.pad(mac_str)
+    }
+}
+
+impl Serialize for FormattedMac {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// `{:x}` formats as lowercase & colon separated
+impl std::fmt::LowerHex for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.format(MacFormat::new(MacNotation::Colon, MacCase::Lower)), f)
+    }
+}
+
+/// `{:X}` formats as uppercase & colon separated, same as `Display`
+impl std::fmt::UpperHex for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.format(MacFormat::new(MacNotation::Colon, MacCase::Upper)), f)
+    }
+}
@@ -0,0 +1,121 @@
+use super::{parse_mac_bytes, MacAddress, MacParseError};
+
+/// A range of MAC Addresses sharing the same leading bits, written like `74:83:C2:00:00:00/24`.
+///
+/// A prefix length of 24 covers a vendor OUI, longer prefixes narrow it down to a manufacturing batch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MacMask {
+    base: MacAddress,
+    prefix_len: u8,
+}
+
+impl MacMask {
+    /// Every bit of the MAC Address is compared
+    pub const MAX_PREFIX_LEN: u8 = 48;
+
+    /// Any bits past the prefix length are cleared from `base`
+    #[inline]
+    pub fn new(base: MacAddress, prefix_len: u8) -> Option<Self> {
+        if prefix_len > Self::MAX_PREFIX_LEN {
+            return None;
+        }
+        let base = MacAddress(base.0 & Self::mask_bits(prefix_len));
+        Some(Self { base, prefix_len })
+    }
+
+    #[inline]
+    const fn mask_bits(prefix_len: u8) -> u64 {
+        // a prefix length of 0 matches everything, shifting a u64 by 64 would overflow
+        match prefix_len {
+            0 => 0,
+            _ => (0xFFFFFF_FFFFFF >> (48 - prefix_len as u32)) << (48 - prefix_len as u32),
+        }
+    }
+
+    /// Returns true if the MAC Address is in the range
+    #[inline]
+    pub fn contains(&self, mac: MacAddress) -> bool {
+        mac.0 & Self::mask_bits(self.prefix_len) == self.base.0
+    }
+
+    /// Returns true if the string looks like a MAC Address with a prefix length
+    #[inline]
+    pub fn is_mask(input: &str) -> bool {
+        input.contains('/')
+    }
+}
+
+impl std::str::FromStr for MacMask {
+    type Err = MacParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &'static str| MacParseError::InvalidMask {
+            invalid_mask: Box::from(input),
+            reason,
+        };
+
+        let (mac_str, prefix_len_str) = input
+            .split_once('/')
+            .ok_or_else(|| invalid("expected a prefix length after `/`"))?;
+        let base = parse_mac_bytes(mac_str.trim().as_bytes())
+            .ok_or_else(|| invalid("the part before `/` must be a full MAC Address"))?;
+        let prefix_len: u8 = prefix_len_str
+            .trim()
+            .parse()
+            .map_err(|_| invalid("the prefix length must be a number from 0 to 48"))?;
+        Self::new(base, prefix_len).ok_or_else(|| invalid("the prefix length must be a number from 0 to 48"))
+    }
+}
+
+impl std::fmt::Display for MacMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.base, self.prefix_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(s: &str) -> MacAddress {
+        MacAddress::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn contains_only_the_prefix() {
+        let oui: MacMask = "74:83:C2:3F:A2:10/24".parse().unwrap();
+        assert!(oui.contains(mac("74:83:c2:00:00:00")));
+        assert!(oui.contains(mac("74:83:c2:ff:ff:ff")));
+        assert!(!oui.contains(mac("74:83:c3:00:00:00")));
+
+        let odd: MacMask = "74:83:c2:3f:a2:10/27".parse().unwrap();
+        assert!(odd.contains(mac("74:83:c2:3f:a2:10")));
+        assert!(odd.contains(mac("74:83:c2:20:00:00")));
+        assert!(!odd.contains(mac("74:83:c2:40:00:00")));
+    }
+
+    #[test]
+    fn prefix_length_bounds() {
+        let everything: MacMask = "00:00:00:00:00:00/0".parse().unwrap();
+        assert!(everything.contains(mac("ff:ff:ff:ff:ff:ff")));
+
+        let exact: MacMask = "74:83:c2:3f:a2:10/48".parse().unwrap();
+        assert!(exact.contains(mac("74:83:c2:3f:a2:10")));
+        assert!(!exact.contains(mac("74:83:c2:3f:a2:11")));
+
+        assert!(MacMask::new(mac("74:83:c2:3f:a2:10"), 49).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_masks() {
+        for input in ["74:83:c2:3f:a2:10", "74:83:c2/24", "74:83:c2:3f:a2:10/49", "74:83:c2:3f:a2:10/-1", "74:83:c2:3f:a2:10/"] {
+            assert!(input.parse::<MacMask>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn displays_the_cleared_base() {
+        let mask: MacMask = " 7483.c23f.a210 / 24 ".parse().unwrap();
+        assert_eq!(mask.to_string(), "74:83:C2:00:00:00/24");
+    }
+}
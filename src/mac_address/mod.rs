@@ -2,25 +2,84 @@ use constcat::concat;
 use serde::{de::{self, Unexpected}, Deserialize};
 use thiserror::Error;
 
+/// Build time generated DFA for validating MAC Addresses
 pub mod validation;
 use validation::MAC_ADDR_REGEX_STR;
 
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
-pub(crate) struct MacAddress(u64);
+/// Build time generated OUI vendor table
+pub mod vendor;
+
+/// Output formats of a MAC Address
+pub mod format;
+pub use format::{FormattedMac, MacCase, MacFormat, MacNotation};
+
+mod mask;
+pub use mask::MacMask;
+
+mod pattern;
+pub use pattern::MacPattern;
+
+mod scan;
+pub use scan::extract_macs;
+
+mod arith;
+
+mod eui64;
+pub use eui64::parse_ipv6;
+
+/// A 48 bit MAC Address, parsed from any of the common notations & displayed as `XX:XX:XX:XX:XX:XX`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress(u64);
 
 impl MacAddress {
+    /// Panics if the value is larger than 6 bytes
     pub fn new(n: u64) -> MacAddress {
         assert!(n <= 0xFFFFFF_FFFFFF, "MAC Address value is larger than what fits in 6 bytes");
         MacAddress(n)
     }
 
+    /// Organizationally Unique Identifier, the first 3 bytes
+    #[inline]
+    pub fn oui(&self) -> u32 {
+        (self.0 >> 24) as u32
+    }
+
+    /// Vendor the OUI is registered to in the IEEE registry.
+    /// Locally administered addresses aren't assigned by a vendor, so they never have one
+    #[inline]
+    pub fn vendor(&self) -> Option<&'static str> {
+        if self.is_locally_administered() {
+            return None;
+        }
+        vendor::lookup_oui(self.oui())
+    }
+
+    /// The I/G bit, set for group (multicast & broadcast) addresses
+    #[inline]
+    pub fn is_multicast(&self) -> bool {
+        (self.0 >> 40) & 0x01 != 0
+    }
+
+    /// The U/L bit, set when the address isn't from the vendor's OUI.
+    /// Phones & laptops use these for randomized private Wi-Fi addresses
     #[inline]
-    pub fn as_bytes(&self) -> [u8; 8] {
-        let b = self.0.to_be_bytes();
-        assert!(b[0] == 0 && b[1] == 0, "MAC Address value is larger than what fits in 6 bytes");
-        b
+    pub fn is_locally_administered(&self) -> bool {
+        (self.0 >> 40) & 0x02 != 0
     }
 
+    /// Locally administered unicast addresses are almost always randomized by the client's OS
+    #[inline]
+    pub fn is_randomized(&self) -> bool {
+        self.is_locally_administered() && !self.is_multicast()
+    }
+
+    // #[inline]
+    // pub fn as_bytes(&self) -> [u8; 8] {
+    //     let b = self.0.to_be_bytes();
+    //     assert!(b[0] == 0 && b[1] == 0, "MAC Address value is larger than what fits in 6 bytes");
+    //     b
+    // }
+
     // #[inline]
     // pub fn into_bytes(self) -> [u8; 8] {
     //     let b = self.0.to_be_bytes();
@@ -41,23 +100,95 @@ impl From<u64> for MacAddress {
     }
 }
 
+/// Error parsing a MAC Address, pattern, or mask, the `Display` text is meant to be shown to the user
 #[derive(Error, Debug)]
-pub(crate) enum MacParseError{
+#[allow(clippy::enum_variant_names)]
+pub enum MacParseError{
     #[error("Invalid MAC Address: {invalid_mac:?}")]
     InvalidMac{ invalid_mac: Box<str> },
+    #[error("Invalid MAC Address pattern {invalid_pattern:?}, {reason}")]
+    InvalidPattern{ invalid_pattern: Box<str>, reason: &'static str },
+    #[error("Invalid MAC Address mask {invalid_mask:?}, {reason}")]
+    InvalidMask{ invalid_mask: Box<str>, reason: &'static str },
 }
 
 impl std::str::FromStr for MacAddress {
     type Err = MacParseError;
 
+    #[inline]
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        if !validation::text_is_valid_mac(input.as_bytes()) {
-            return Err(MacParseError::InvalidMac { invalid_mac: Box::from(input) });
+        parse_mac_bytes(input.as_bytes())
+            .ok_or_else(|| MacParseError::InvalidMac { invalid_mac: Box::from(input) })
+    }
+}
+
+/// Marker bit set in `HEX_LUT` for bytes that aren't hexadecimal characters
+const INVALID_HEX: u8 = 0x10;
+
+/// Maps each ASCII hexadecimal character to its value, every other byte has `INVALID_HEX` set
+static HEX_LUT: [u8; 256] = {
+    let mut lut = [INVALID_HEX; 256];
+    let mut i = 0;
+    while i < 10 {
+        lut[b'0' as usize + i] = i as u8;
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 6 {
+        lut[b'a' as usize + i] = 10 + i as u8;
+        lut[b'A' as usize + i] = 10 + i as u8;
+        i += 1;
+    }
+    lut
+};
+
+/// Validates & decodes a MAC Address in a single pass without allocating.
+/// Accepts the same notations as `MAC_ADDR_REGEX_STR`:
+/// `XX:XX:XX:XX:XX:XX`, `XX-XX-XX-XX-XX-XX`, `XX XX XX XX XX XX`, `XXXX.XXXX.XXXX`, & `XXXXXXXXXXXX`
+#[inline]
+pub fn parse_mac_bytes(bytes: &[u8]) -> Option<MacAddress> {
+    // the notation is determined by the length, only the separator positions differ
+    let mut digits = [0u8; 12];
+    let mut sep_mismatch = 0u8;
+    match bytes.len() {
+        12 => digits.copy_from_slice(bytes),
+        14 => {
+            // XXXX.XXXX.XXXX
+            for group in 0..3 {
+                digits[group * 4..group * 4 + 4].copy_from_slice(&bytes[group * 5..group * 5 + 4]);
+            }
+            sep_mismatch = (bytes[4] ^ b'.') | (bytes[9] ^ b'.');
+        }
+        17 => {
+            // XX?XX?XX?XX?XX?XX, all separators must match the first one
+            let sep = bytes[2];
+            if !matches!(sep, b':' | b'-' | b' ') {
+                return None;
+            }
+            for group in 0..6 {
+                digits[group * 2] = bytes[group * 3];
+                digits[group * 2 + 1] = bytes[group * 3 + 1];
+            }
+            for group in 0..5 {
+                sep_mismatch |= bytes[group * 3 + 2] ^ sep;
+            }
         }
-        let mac_hex = input.replace([':', '-'], "");
-        let num_u64 = u64::from_str_radix(&mac_hex, 16).expect("mac validation failed");
+        _ => return None,
+    }
+
+    let mut invalid = 0u8;
+    let mut num_u64 = 0u64;
+    for digit in digits {
+        let v = HEX_LUT[digit as usize];
+        invalid |= v;
+        num_u64 = (num_u64 << 4) | u64::from(v & 0xF);
+    }
 
-        Ok(MacAddress::new(num_u64))
+    if (invalid & INVALID_HEX) | sep_mismatch == 0 {
+        // 12 hex digits always fit in 6 bytes
+        Some(MacAddress(num_u64))
+    } else {
+        None
     }
 }
 
@@ -79,41 +210,156 @@ impl std::convert::TryFrom<std::borrow::Cow<'_, str>> for MacAddress {
 
 impl std::fmt::Display for MacAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let bytes = self.as_bytes();
-        let _ = write!(
-            f,
-            "{:<02X}:{:<02X}:{:<02X}:{:<02X}:{:<02X}:{:<02X}",
-            bytes[2],
-            bytes[3],
-            bytes[4],
-            bytes[5],
-            bytes[6],
-            bytes[7]
-        );
-
-        Ok(())
+        std::fmt::Display::fmt(&self.format(MacFormat::default()), f)
     }
 }
 
-// impl serde::Serialize for MacAddress {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         serializer.collect_str(self)
-//     }
-// }
+/// Serializes as uppercase & colon separated, use `MacAddress::format` to serialize with a different format
+impl serde::Serialize for MacAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 
 impl<'de> Deserialize<'de> for MacAddress {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: de::Deserializer<'de>,
     {
-        let mac_str: &str = de::Deserialize::deserialize(deserializer)?;
-        MacAddress::try_from(mac_str).map_err(|_| {
-            let unexpected = Unexpected::Str(mac_str);
-            const EXPECTED: &str = concat!("MAC Address in string format matching regex: ", MAC_ADDR_REGEX_STR);
-            de::Error::invalid_value(unexpected, &EXPECTED)
-        })
+        struct MacAddressVisitor;
+
+        impl de::Visitor<'_> for MacAddressVisitor {
+            type Value = MacAddress;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(EXPECTED)
+            }
+
+            // parse straight from the borrowed (or transient) str, no intermediate String is created
+            fn visit_str<E: de::Error>(self, mac_str: &str) -> Result<Self::Value, E> {
+                parse_mac_bytes(mac_str.as_bytes())
+                    .ok_or_else(|| de::Error::invalid_value(Unexpected::Str(mac_str), &EXPECTED))
+            }
+        }
+
+        const EXPECTED: &str = concat!("MAC Address in string format matching regex: ", MAC_ADDR_REGEX_STR);
+        deserializer.deserialize_str(MacAddressVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validation::text_is_valid_mac;
+
+    const MAC: MacAddress = MacAddress(0x7483_C23F_A210);
+
+    #[test]
+    fn parses_every_notation() {
+        for input in [
+            "74:83:C2:3F:A2:10",
+            "74:83:c2:3f:a2:10",
+            "74-83-C2-3F-A2-10",
+            "74 83 C2 3F A2 10",
+            "7483.C23F.A210",
+            "7483.c23f.a210",
+            "7483C23FA210",
+            "7483c23fa210",
+        ] {
+            assert_eq!(parse_mac_bytes(input.as_bytes()), Some(MAC), "{input:?}");
+            assert!(text_is_valid_mac(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in [
+            "",
+            // mixed separators
+            "74:83-C2:3F:A2:10",
+            "74:83:C2:3F:A2-10",
+            "74 83:C2 3F:A2 10",
+            "7483.C23F-A210",
+            // wrong group lengths
+            "748:3C2:3F:A2:10:",
+            "7483:C23F:A210",
+            "74.83.C2.3F.A2.10",
+            "748.3C23F.A210",
+            "7483C23FA21",
+            "7483C23FA2100",
+            // unsupported separators
+            "74_83_C2_3F_A2_10",
+            "74/83/C2/3F/A2/10",
+            // trailing & leading junk
+            "74:83:C2:3F:A2:10 ",
+            " 74:83:C2:3F:A2:10",
+            "74:83:C2:3F:A2:10x",
+            "7483C23FA210\n",
+            // non hex digits
+            "74:83:C2:3F:A2:1G",
+            "G483C23FA210",
+            "7483.C23F.A21Z",
+        ] {
+            assert_eq!(parse_mac_bytes(input.as_bytes()), None, "{input:?}");
+            assert!(!text_is_valid_mac(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn matches_the_dfa() {
+        // random strings built from hex digits, separators, & a few invalid bytes,
+        // at the lengths of each notation so the separator checks are exercised
+        const ALPHABET: &[u8] = b"0123456789abcdefABCDEF:-. _gG/";
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next_random = || {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..200_000 {
+            let len = [12, 14, 17][(next_random() % 3) as usize];
+            let mut input: Vec<u8> = (0..len)
+                .map(|_| ALPHABET[(next_random() % ALPHABET.len() as u64) as usize])
+                .collect();
+            // most random strings are invalid, make roughly half of them a valid notation with a single changed byte
+            if next_random() % 2 == 0 {
+                let mac = MacAddress(next_random() & 0xFFFFFF_FFFFFF);
+                let notation = MacNotation::ALL[(next_random() % MacNotation::ALL.len() as u64) as usize];
+                input = mac.format(MacFormat::new(notation, MacCase::Lower)).to_string().into_bytes();
+                let i = (next_random() % input.len() as u64) as usize;
+                input[i] = ALPHABET[(next_random() % ALPHABET.len() as u64) as usize];
+            }
+            assert_eq!(
+                parse_mac_bytes(&input).is_some(),
+                text_is_valid_mac(&input),
+                "{:?}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let mac = MacAddress::new(0x0000_0000_0001);
+        assert_eq!(mac.to_string(), "00:00:00:00:00:01");
+        assert_eq!(mac.to_string().parse::<MacAddress>().unwrap(), mac);
+    }
+
+    #[test]
+    fn looks_up_the_vendor() {
+        assert_eq!("74:83:c2:3f:a2:10".parse::<MacAddress>().unwrap().vendor(), Some("Ubiquiti Inc"));
+        assert_eq!(MacAddress::new(0x0000_0000_0001).vendor(), None);
+    }
+
+    /// The repository only has a subset of the registry, this passes once `src/misc/oui.csv` is replaced with the full csv
+    #[test]
+    #[ignore = "needs the full IEEE registry in src/misc/oui.csv"]
+    fn looks_up_vendors_outside_the_bundled_subset() {
+        assert_eq!("3c:22:fb:00:00:01".parse::<MacAddress>().unwrap().vendor(), Some("Apple, Inc."));
     }
 }
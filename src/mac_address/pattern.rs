@@ -0,0 +1,177 @@
+use super::{MacAddress, MacParseError, HEX_LUT, INVALID_HEX};
+
+/// A partial MAC Address where some of the hexadecimal digits are unknown.
+///
+/// `?` matches any single hex digit & `*` matches any number of hex digits, so all of these are valid:
+/// `*:3F:A2:10` (suffix), `74:83:C2:*` (prefix), & `??:??:??:3f:a2:1?`.
+/// Separators (`:`, `-`, `.`, & spaces) are ignored.
+/// 6 to 11 hex digits without any wildcard or separator, ie `3FA210` read off a label, are the end of the MAC Address.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MacPattern {
+    value: u64,
+    /// `0xF` for every nibble that must match `value`
+    mask: u64,
+}
+
+impl MacPattern {
+    /// Fewest hex digits accepted without a wildcard, as many as the NIC specific half of the MAC Address
+    pub const MIN_SUFFIX_LEN: usize = 6;
+
+    /// Returns true if every known hex digit matches the MAC Address
+    #[inline]
+    pub fn matches(&self, mac: MacAddress) -> bool {
+        mac.0 & self.mask == self.value
+    }
+
+    /// Returns true if the string contains a wildcard character or is only the last few hex digits, meaning it should be parsed as a pattern
+    #[inline]
+    pub fn is_pattern(input: &str) -> bool {
+        input.contains(['*', '?']) || Self::is_suffix(input)
+    }
+
+    /// 6 to 11 hex digits & nothing else, matched against the end of the MAC Address as if it started with `*`.
+    /// Fewer digits would match too many devices, & are more likely a typo than a partial MAC Address
+    #[inline]
+    fn is_suffix(input: &str) -> bool {
+        (Self::MIN_SUFFIX_LEN..12).contains(&input.len()) && input.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+}
+
+impl std::str::FromStr for MacPattern {
+    type Err = MacParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &'static str| MacParseError::InvalidPattern {
+            invalid_pattern: Box::from(input),
+            reason,
+        };
+
+        let tokens = || input.bytes().filter(|b| !matches!(b, b':' | b'-' | b'.' | b' '));
+        let nibble_count = tokens().filter(|&b| b != b'*').count();
+        let star_count = tokens().filter(|&b| b == b'*').count();
+        if star_count > 1 {
+            return Err(invalid("only one `*` is allowed"));
+        }
+        if nibble_count > 12 {
+            return Err(invalid("a MAC Address only has 12 hexadecimal digits"));
+        }
+        // a suffix is right aligned like a leading `*`, which shifting the value & mask by 0 already does
+        if star_count == 0 && nibble_count != 12 && !Self::is_suffix(input) {
+            return Err(invalid("use `*` in place of the missing hexadecimal digits, or enter at least the last 6 digits without separators"));
+        }
+        // the `*` fills in however many nibbles are missing
+        let star_len = 12 - nibble_count;
+
+        let mut value = 0u64;
+        let mut mask = 0u64;
+        for b in tokens() {
+            match b {
+                b'*' => {
+                    value <<= star_len * 4;
+                    mask <<= star_len * 4;
+                }
+                b'?' => {
+                    value <<= 4;
+                    mask <<= 4;
+                }
+                _ => {
+                    let v = HEX_LUT[b as usize];
+                    if v & INVALID_HEX != 0 {
+                        return Err(invalid("only hexadecimal characters, separators, `?`, & `*` are allowed"));
+                    }
+                    value = (value << 4) | u64::from(v);
+                    mask = (mask << 4) | 0xF;
+                }
+            }
+        }
+
+        if mask == 0 {
+            return Err(invalid("at least one hexadecimal digit is required"));
+        }
+        Ok(Self { value, mask })
+    }
+}
+
+/// Displayed like `XX:XX:XX:XX:XX:XX` with `?` in place of unknown digits
+impl std::fmt::Display for MacPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        let mut buf = [0u8; 17];
+        let mut len = 0;
+        for nibble_idx in 0..12 {
+            if nibble_idx != 0 && nibble_idx % 2 == 0 {
+                buf[len] = b':';
+                len += 1;
+            }
+            let shift = 44 - nibble_idx * 4;
+            buf[len] = if (self.mask >> shift) & 0xF == 0 {
+                b'?'
+            } else {
+                HEX_DIGITS[((self.value >> shift) & 0xF) as usize]
+            };
+            len += 1;
+        }
+        // SAFETY: only ascii hex digits, `?`, & `:` are written to the buffer
+        f.pad(std::str::from_utf8(&buf[..len]).expect("formatted MAC pattern should be ascii"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(s: &str) -> MacAddress {
+        MacAddress::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn suffix_prefix_and_single_digit_wildcards() {
+        let suffix: MacPattern = "*:3F:A2:10".parse().unwrap();
+        assert!(suffix.matches(mac("74:83:c2:3f:a2:10")));
+        assert!(!suffix.matches(mac("74:83:c2:3f:a2:11")));
+
+        let prefix: MacPattern = "74-83-C2-*".parse().unwrap();
+        assert!(prefix.matches(mac("74:83:c2:00:00:00")));
+        assert!(!prefix.matches(mac("74:83:c3:00:00:00")));
+
+        let single: MacPattern = "??:??:??:3f:a2:1?".parse().unwrap();
+        assert!(single.matches(mac("00:00:00:3f:a2:1f")));
+        assert!(!single.matches(mac("00:00:00:3f:a2:20")));
+
+        let middle: MacPattern = "74:83:*:10".parse().unwrap();
+        assert!(middle.matches(mac("74:83:c2:3f:a2:10")));
+        assert!(!middle.matches(mac("74:84:c2:3f:a2:10")));
+    }
+
+    #[test]
+    fn bare_hex_digits_are_a_suffix() {
+        assert!(MacPattern::is_pattern("3FA210"));
+        assert!(MacPattern::is_pattern("C23FA210"));
+        assert!(!MacPattern::is_pattern("7483c23fa210"));
+        assert!(!MacPattern::is_pattern("3f:a2:10"));
+        assert!(!MacPattern::is_pattern("3FA21G"));
+        assert_eq!("3FA210".parse::<MacPattern>().unwrap(), "*3FA210".parse::<MacPattern>().unwrap());
+        assert!("3fa210".parse::<MacPattern>().unwrap().matches(mac("74:83:c2:3f:a2:10")));
+    }
+
+    #[test]
+    fn rejects_short_bare_hex_digits() {
+        for input in ["a", "0", "a210", "fa210"] {
+            assert!(!MacPattern::is_pattern(input), "{input}");
+            assert!(input.parse::<MacPattern>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for input in ["*:3f:*", "74:83:c2:3f:a2:10:1*", "3f:a2:10", "*:3f:g2", "*", "??:??"] {
+            assert!(input.parse::<MacPattern>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn displays_unknown_digits() {
+        assert_eq!("*:3f:a2:1?".parse::<MacPattern>().unwrap().to_string(), "??:??:??:3F:A2:1?");
+        assert_eq!("74:83:c2:*".parse::<MacPattern>().unwrap().to_string(), "74:83:C2:??:??:??");
+    }
+}
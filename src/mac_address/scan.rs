@@ -0,0 +1,160 @@
+use super::{parse_mac_bytes, MacAddress};
+use std::collections::HashSet;
+
+/// Lengths of each notation accepted by `parse_mac_bytes`, longest first
+const NOTATION_LENGTHS: [usize; 3] = [17, 14, 12];
+
+/// Iterator over every MAC Address found in free-form text, such as ticket text,
+/// `show mac address-table` output, or ARP tables. All notations accepted by `parse_mac_bytes` are found.
+///
+/// A MAC Address must not be directly attached to other letters or digits, so longer hex strings
+/// (ie EUI-64 addresses or hashes) aren't partially matched.
+/// MAC Addresses separated by spaces need at least one letter, so runs of numbers like `10 20 30 40 50 60` aren't matched.
+pub struct MacScanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MacScanner<'a> {
+    #[inline]
+    pub fn new(text: &'a str) -> Self {
+        Self { text: text.as_bytes(), pos: 0 }
+    }
+
+    /// The byte before a MAC Address can't be part of a word, or a separator following another hex group,
+    /// ie `MAC:aa:bb:cc:dd:ee:ff` is matched but not `00:aa:bb:cc:dd:ee:ff`.
+    /// A space only counts as a separator if the MAC Address is separated by spaces, so `1 0011.2233.4455` is matched
+    #[inline]
+    fn is_start_boundary(&self, start: usize, candidate: &[u8]) -> bool {
+        let Some(prev) = start.checked_sub(1).map(|i| self.text[i]) else {
+            return true;
+        };
+        let separated_by_spaces = candidate.get(2) == Some(&b' ');
+        match prev {
+            b if b.is_ascii_alphanumeric() => false,
+            b':' | b'-' | b'.' | b'_' => !self.follows_hex_group(start - 1),
+            b' ' if separated_by_spaces => !self.follows_hex_group(start - 1),
+            _ => true,
+        }
+    }
+
+    /// Returns true if the word ending right before `end` is only hex digits
+    #[inline]
+    fn follows_hex_group(&self, end: usize) -> bool {
+        let before = &self.text[..end];
+        let word_start = before.iter().rposition(|b| !b.is_ascii_alphanumeric()).map_or(0, |i| i + 1);
+        let word = &before[word_start..];
+        !word.is_empty() && word.iter().all(u8::is_ascii_hexdigit)
+    }
+
+    /// Numbers separated by spaces are far more common than MAC Addresses without any of the hex digits `a-f`
+    #[inline]
+    fn has_letter_if_spaced(candidate: &[u8]) -> bool {
+        candidate.get(2) != Some(&b' ') || candidate.iter().any(u8::is_ascii_alphabetic)
+    }
+
+    /// The byte after a MAC Address can't be part of a word, or a separator followed by another hex group,
+    /// ie `aa:bb:cc:dd:ee:ff:00` isn't matched. Like the start, a space only counts if the MAC Address is separated by spaces
+    #[inline]
+    fn is_end_boundary(&self, end: usize, candidate: &[u8]) -> bool {
+        let separated_by_spaces = candidate.get(2) == Some(&b' ');
+        match self.text.get(end) {
+            None => true,
+            Some(&b) if b.is_ascii_alphanumeric() || b == b'_' => false,
+            Some(b':' | b'-' | b'.') => !self.precedes_hex_group(end + 1),
+            Some(b' ') if separated_by_spaces => !self.precedes_hex_group(end + 1),
+            Some(_) => true,
+        }
+    }
+
+    /// Returns true if the word starting at `start` is only hex digits
+    #[inline]
+    fn precedes_hex_group(&self, start: usize) -> bool {
+        let after = &self.text[start.min(self.text.len())..];
+        let word_len = after.iter().position(|b| !b.is_ascii_alphanumeric()).unwrap_or(after.len());
+        let word = &after[..word_len];
+        !word.is_empty() && word.iter().all(u8::is_ascii_hexdigit)
+    }
+}
+
+impl Iterator for MacScanner<'_> {
+    type Item = MacAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.text.len() {
+            let start = self.pos;
+            if self.text[start].is_ascii_hexdigit() {
+                for len in NOTATION_LENGTHS {
+                    let end = start + len;
+                    let Some(candidate) = self.text.get(start..end) else {
+                        continue;
+                    };
+                    if !self.is_start_boundary(start, candidate) || !Self::has_letter_if_spaced(candidate) {
+                        continue;
+                    }
+                    if let Some(mac) = parse_mac_bytes(candidate).filter(|_| self.is_end_boundary(end, candidate)) {
+                        self.pos = end;
+                        return Some(mac);
+                    }
+                }
+            }
+            self.pos += 1;
+        }
+        None
+    }
+}
+
+/// Every unique MAC Address in the text, in the order they first appear
+pub fn extract_macs(text: &str) -> Vec<MacAddress> {
+    let mut seen = HashSet::new();
+    MacScanner::new(text).filter(|mac| seen.insert(*mac)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn macs(text: &str) -> Vec<String> {
+        extract_macs(text).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn finds_every_notation_in_text() {
+        let text = "ticket: ap 74:83:c2:3f:a2:10 is down, switch sees 7483.c23f.a212\n\
+            arp: 10.0.0.5 at 74-83-C2-3F-A2-14 [ether] & 7483c23fa216 & 74 83 c2 3f a2 18";
+        assert_eq!(
+            macs(text),
+            ["74:83:C2:3F:A2:10", "74:83:C2:3F:A2:12", "74:83:C2:3F:A2:14", "74:83:C2:3F:A2:16", "74:83:C2:3F:A2:18"]
+        );
+        assert_eq!(macs("74:83:c2:3f:a2:10, 74-83-c2-3f-a2-10"), ["74:83:C2:3F:A2:10"]);
+    }
+
+    #[test]
+    fn matches_after_a_label_with_a_separator() {
+        assert_eq!(macs("MAC:aa:bb:cc:dd:ee:ff"), ["AA:BB:CC:DD:EE:FF"]);
+        assert_eq!(macs("mac=host_aa-bb-cc-dd-ee-ff"), ["AA:BB:CC:DD:EE:FF"]);
+        assert_eq!(macs("client-aabb.ccdd.eeff"), ["AA:BB:CC:DD:EE:FF"]);
+        assert_eq!(macs("1 0011.2233.4455 DYNAMIC Gi0/1"), ["00:11:22:33:44:55"]);
+        assert_eq!(macs("(aa:bb:cc:dd:ee:ff)"), ["AA:BB:CC:DD:EE:FF"]);
+        assert_eq!(macs("aa:bb:cc:dd:ee:ff.Done"), ["AA:BB:CC:DD:EE:FF"]);
+    }
+
+    #[test]
+    fn ignores_longer_hex_strings() {
+        assert!(macs("00:aa:bb:cc:dd:ee:ff").is_empty());
+        assert!(macs("aa:bb:cc:dd:ee:ff:00").is_empty());
+        assert!(macs("fe80::7683:c2ff:fe3f:a210").is_empty());
+        assert!(macs("sha 0123456789abcdef0123").is_empty());
+        assert!(macs("xaa:bb:cc:dd:ee:ff").is_empty());
+        assert!(macs("aa:bb:cc:dd:ee:ffx").is_empty());
+        assert!(macs("aabb.ccdd.eeff.0011").is_empty());
+    }
+
+    #[test]
+    fn ignores_numbers_separated_by_spaces() {
+        assert!(macs("10 20 30 40 50 60").is_empty());
+        assert!(macs("counts: 01 10 20 30 40 50 60 70").is_empty());
+        assert!(macs("ab 74 83 c2 3f a2 10").is_empty());
+        assert_eq!(macs("ap 74 83 c2 3f a2 10 offline"), ["74:83:C2:3F:A2:10"]);
+    }
+}
@@ -7,7 +7,7 @@ use regex_automata::{
 	Anchored, Input,
 };
 
-pub(crate) const MAC_ADDR_REGEX_STR: &str = "^(?:(?:[0-9A-Fa-f]{2}:){5}|(?:[0-9A-Fa-f]{2}-){5})[0-9A-Fa-f]{2}$";
+pub const MAC_ADDR_REGEX_STR: &str = "^(?:(?:(?:[0-9A-Fa-f]{2}:){5}|(?:[0-9A-Fa-f]{2}-){5}|(?:[0-9A-Fa-f]{2} ){5})[0-9A-Fa-f]{2}|(?:[0-9A-Fa-f]{4}\\.){2}[0-9A-Fa-f]{4}|[0-9A-Fa-f]{12})$";
 
 pub static MAC_ADDR_REGEX: Lazy<dense::DFA<&'static [u32]>> = Lazy::new(|| {
 	static ALIGNED: &AlignAs<[u8], u32> = &AlignAs {
@@ -21,7 +21,8 @@ pub static MAC_ADDR_REGEX: Lazy<dense::DFA<&'static [u32]>> = Lazy::new(|| {
 	dense_dfa
 });
 
-pub(crate) fn text_is_valid_mac<S: AsRef<[u8]>>(bytes: S) -> bool {
+/// Returns true if the bytes are a full MAC Address in any of the supported notations
+pub fn text_is_valid_mac<S: AsRef<[u8]>>(bytes: S) -> bool {
 	MAC_ADDR_REGEX
 		.try_search_fwd(&Input::new(&bytes).anchored(Anchored::Yes).earliest(true))
 		.is_ok_and(|x| x.is_some())
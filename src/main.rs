@@ -1,12 +1,25 @@
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
+#![cfg_attr(all(not(debug_assertions), feature = "gui"), windows_subsystem = "windows")] // hide console window on Windows in release
 
+mod cli;
+#[cfg(feature = "gui")]
 mod gui;
-mod mac_address;
-mod unifi;
 
-use gui::app::GuiApp;
+use std::process::ExitCode;
+
+#[cfg(not(feature = "gui"))]
+fn main() -> ExitCode {
+    cli::run()
+}
+
+#[cfg(feature = "gui")]
+fn main() -> ExitCode {
+    use gui::app::GuiApp;
+
+    // any arguments run the command line interface instead of the GUI
+    if cli::is_cli_invocation() {
+        return cli::run();
+    }
 
-fn main() {
     const IMAGE: &[u8] = include_bytes!("unifi-search.ico");
     let icon = load_icon(IMAGE);
 
@@ -22,11 +35,14 @@ fn main() {
         native_options,
         Box::new(|cc| Ok(Box::new(GuiApp::new(cc)))),
     );
-    if error.is_err() {
-        eprintln!("{}", error.unwrap_err());
+    if let Err(e) = error {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
     }
+    ExitCode::SUCCESS
 }
 
+#[cfg(feature = "gui")]
 fn load_icon(image_const: &[u8]) -> egui::viewport::IconData {
     let (icon_rgba, icon_width, icon_height) = {
         //let image = image::open(path)
@@ -1,18 +1,17 @@
 #![allow(dead_code)]
 
-use super::devices::{
-    ClientDevice, ClientDeviceActive, UnifiDeviceBasic, /*UnifiDeviceFull,*/ UnifiSite,
-};
-use reqwest::{
-    blocking::Client,
-    header::REFERER,
-    StatusCode,
+use super::{
+    async_api::AsyncUnifiClient,
+    builder::UnifiClientBuilder,
+    cancel::CancellationToken,
+    controller::{ControllerInfo, UnifiAdmin, UnifiStatusMeta},
+    devices::{ClientDevice, ClientDeviceActive, UnifiDeviceBasic, /*UnifiDeviceFull,*/ UnifiSite},
 };
 use serde::Deserialize;
-use std::collections::HashMap;
-use std::time::Duration;
 use thiserror::Error;
-use zeroize::Zeroize;
+use reqwest::Url;
+use std::sync::Arc;
+use tokio::runtime::{self, Runtime};
 
 #[derive(Debug, Clone, Deserialize)]
 struct RespMeta {
@@ -29,15 +28,26 @@ enum RespResult {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-struct UnifiSitesResp {
+pub(super) struct UnifiSitesResp {
     meta: RespMeta,
-    data: Vec<UnifiSite>,
+    pub(super) data: Vec<UnifiSite>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct UnifiStatusResp {
+    pub(super) meta: UnifiStatusMeta,
 }
 
 #[derive(Debug, Clone, Deserialize)]
-struct UnifiDevicesBasicResp {
+pub(super) struct UnifiSelfResp {
     meta: RespMeta,
-    data: Vec<UnifiDeviceBasic>,
+    pub(super) data: Vec<UnifiAdmin>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct UnifiDevicesBasicResp {
+    meta: RespMeta,
+    pub(super) data: Vec<UnifiDeviceBasic>,
 }
 
 // #[derive(Debug, Clone, Deserialize)]
@@ -47,194 +57,176 @@ struct UnifiDevicesBasicResp {
 // }
 
 #[derive(Debug, Clone, Deserialize)]
-struct UnifiClientsAllResp {
+pub(super) struct UnifiClientsAllResp {
     meta: RespMeta,
-    data: Vec<ClientDevice>,
+    pub(super) data: Vec<ClientDevice>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
-struct UnifiClientsActiveResp {
+pub(super) struct UnifiClientsActiveResp {
     meta: RespMeta,
-    data: Vec<ClientDeviceActive>,
+    pub(super) data: Vec<ClientDeviceActive>,
 }
 
+/// Errors from the Unifi controller API
 #[derive(Error, Debug)]
-pub(crate) enum UnifiAPIError {
+#[allow(clippy::enum_variant_names)]
+pub enum UnifiAPIError {
+    /// The HTTP client couldn't be built, ie the TLS backend failed to initialize
     #[error("Error building reqwest client")]
     ClientError{ source: reqwest::Error },
+    /// The server URL couldn't be parsed or isn't http or https
+    #[error("Invalid server URL {url:?}, {reason}")]
+    InvalidUrl { url: Box<str>, reason: Box<str> },
+    /// The proxy URL couldn't be parsed or isn't a supported scheme
+    #[error("Invalid proxy URL {url:?}, {reason}")]
+    InvalidProxy { url: Box<str>, reason: Box<str> },
+    /// The controller rejected the username or password
     #[error("Invalid credentials")]
     LoginAuthenticationError { url: Box<str> },
+    /// The controller couldn't be reached or returned an HTTP error
     #[error("Error communicating with Unifi API, check your URL & try again")]
     ReqwestError{ source: reqwest::Error },
+    /// The controller's response wasn't the expected json
     #[error("Error parsing json from\n{url}")]
     JsonError {
         url: Box<str>,
         source: simd_json::Error,
     },
+    /// The blocking client's async runtime couldn't be started
+    #[error("Error starting the async runtime")]
+    RuntimeError{ source: std::io::Error },
+    /// The cancellation token was canceled while waiting on the controller
+    #[error("The request was canceled")]
+    Canceled,
 }
 
-pub(crate) struct UnifiClient<'a> {
-    client: Client,
-    server_url: &'a str,
-    is_logged_in: bool,
+/// Blocking client for a single Unifi controller, the session cookie is kept after logging in.
+/// A thin wrapper that runs `AsyncUnifiClient` on a single threaded runtime,
+/// so it can't be used from within an async runtime.
+/// Clones share the session & runtime, so a clone can be kept for each thread
+#[derive(Debug, Clone)]
+pub struct UnifiClient {
+    runtime: Arc<Runtime>,
+    client: AsyncUnifiClient,
 }
 
-impl<'a> UnifiClient<'a> {
-    pub(crate) fn new(
-        server_url: &'a str,
+// the client is meant to be cached & shared between threads
+const _: fn() = || {
+    fn assert_shareable<T: Send + Sync + Clone>() {}
+    assert_shareable::<UnifiClient>();
+    assert_shareable::<AsyncUnifiClient>();
+};
+
+impl UnifiClient {
+    /// `server_url` is the controller URL, ie `https://unifi.example.com:8443`.
+    /// Use `UnifiClient::builder` for any other settings
+    pub fn new(
+        server_url: &str,
         accept_invalid_certs: bool,
     ) -> Result<Self, UnifiAPIError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(15))
-            .danger_accept_invalid_certs(accept_invalid_certs)
-            .cookie_store(true)
+        UnifiClient::builder(server_url)
+            .accept_invalid_certs(accept_invalid_certs)
             .build()
-            .map_err(|source| UnifiAPIError::ClientError{ source })?;
+    }
+
+    /// `server_url` is the controller URL, ie `https://unifi.example.com:8443`
+    pub fn builder(server_url: &str) -> UnifiClientBuilder {
+        UnifiClientBuilder::new(server_url)
+    }
+
+    pub(super) fn from_async(client: AsyncUnifiClient) -> Result<Self, UnifiAPIError> {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|source| UnifiAPIError::RuntimeError{ source })?;
 
         Ok(Self {
+            runtime: Arc::new(runtime),
             client,
-            server_url,
-            is_logged_in: false,
         })
     }
 
-    pub(crate) fn is_logged_in(&self) -> bool {
-        self.is_logged_in
+    /// The async client the requests run on, it shares the session
+    pub fn as_async(&self) -> &AsyncUnifiClient {
+        &self.client
+    }
+
+    /// Controller URL without a trailing slash
+    pub fn server_url(&self) -> &Url {
+        self.client.server_url()
+    }
+
+    /// True if the port of the server URL was chosen by `probe_server_url`
+    pub fn server_url_was_probed(&self) -> bool {
+        self.client.server_url_was_probed()
+    }
+
+    /// When the server URL doesn't include a port, 443 & then 8443 are tried, see `AsyncUnifiClient::probe_server_url`
+    pub fn probe_server_url(&self) -> Result<bool, UnifiAPIError> {
+        self.runtime.block_on(self.client.probe_server_url())
     }
 
-    pub(crate) fn login(
-        &mut self,
+    /// Requests are dropped as soon as the token is canceled & return `UnifiAPIError::Canceled`,
+    /// instead of waiting for the controller to respond or the request to time out
+    pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.client = self.client.with_cancel_token(cancel_token);
+        self
+    }
+
+    /// Returns true once `login` succeeds
+    pub fn is_logged_in(&self) -> bool {
+        self.client.is_logged_in()
+    }
+
+    /// True if the controller is a UniFi OS console, known once `login` succeeds
+    pub fn is_unifi_os(&self) -> bool {
+        self.client.is_unifi_os()
+    }
+
+    /// Logs in with `/api/login`, or `/api/auth/login` on UniFi OS.
+    /// The username & password are zeroized once the request is built
+    pub fn login(
+        &self,
         username: &mut str,
         password: &mut str,
     ) -> Result<(), UnifiAPIError> {
-        let mut login_data: HashMap<&str, &mut str> = HashMap::new();
-        login_data.insert("username", username);
-        login_data.insert("password", password);
-
-        let url = format!("{}/api/login", self.server_url).into_boxed_str();
-        let login_result = self
-            .client
-            .post(url.as_ref())
-            .header(REFERER, "/login")
-            .json(&login_data)
-            .send();
-
-        {
-            // zeroize the user entered data for security
-            login_data.iter_mut().for_each(|(_, v)| {
-                v.zeroize();
-            });
-            std::mem::drop(login_data);
-            password.zeroize();
-            username.zeroize();
-        };
-
-        let login = {
-            let login_response = login_result
-                .map_err(|source| UnifiAPIError::ReqwestError {
-                    source,
-                })?;
-            // if controller returns HTTP 400, creds were bad
-            if login_response.status() == StatusCode::BAD_REQUEST {
-                return Err(UnifiAPIError::LoginAuthenticationError { url });
-            }
-            login_response
-                .error_for_status()
-                .map_err(|source| UnifiAPIError::ReqwestError {
-                    source,
-                })?
-        };
-        if login.status().is_success() {
-            self.is_logged_in = true;
-            Ok(())
-        } else {
-            Err(UnifiAPIError::LoginAuthenticationError { url })
-        }
-    }
-
-    fn api_call(
-        &mut self,
-        url: &str,
-    ) -> Result<reqwest::blocking::Response, UnifiAPIError> {
-        let resp = self.client
-            .get(url)
-            .send()
-            .map_err(|source| UnifiAPIError::ReqwestError {
-                source
-            })?
-            .error_for_status()
-            .map_err(|source| UnifiAPIError::ReqwestError {
-                source
-            })?;
-        Ok(resp)
-    }
-
-    pub(crate) fn get_sites(&mut self) -> Result<Vec<UnifiSite>, UnifiAPIError> {
-        let url = format!("{}/api/self/sites", self.server_url).into_boxed_str();
-        let resp = self.api_call(&url)?;
-        let sites: UnifiSitesResp = simd_json::serde::from_reader(resp)
-            .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-        Ok(sites.data)
-    }
-
-    pub(crate) fn get_site_devices_basic(
-        &mut self,
+        self.runtime.block_on(self.client.login(username, password))
+    }
+
+    /// Requests the version & the account's permissions, the result is kept for `controller_info`
+    pub fn get_controller_info(&self) -> Result<ControllerInfo, UnifiAPIError> {
+        self.runtime.block_on(self.client.get_controller_info())
+    }
+
+    /// The result of `get_controller_info`, if it was requested
+    pub fn controller_info(&self) -> Option<&ControllerInfo> {
+        self.client.controller_info()
+    }
+
+    /// Every site the user has access to
+    pub fn get_sites(&self) -> Result<Vec<UnifiSite>, UnifiAPIError> {
+        self.runtime.block_on(self.client.get_sites())
+    }
+
+    /// Every site in the controller, including sites the user isn't assigned to. Only super admins are allowed to use this
+    pub fn get_all_sites(&self) -> Result<Vec<UnifiSite>, UnifiAPIError> {
+        self.runtime.block_on(self.client.get_all_sites())
+    }
+
+    /// Every device adopted to the site, `site_code` is `UnifiSite::code`
+    pub fn get_site_devices_basic(
+        &self,
         site_code: &str,
     ) -> Result<Vec<UnifiDeviceBasic>, UnifiAPIError> {
-        let url =
-            format!("{}/api/s/{}/stat/device-basic", self.server_url, site_code).into_boxed_str();
-        let resp = self.api_call(&url)?;
-        let site_unifi_devices_basic: UnifiDevicesBasicResp =
-            simd_json::serde::from_reader(resp)
-                .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-        Ok(site_unifi_devices_basic.data)
-    }
-
-    // pub(crate) fn get_site_devices_full(
-    //     &mut self,
-    //     site_code: &str,
-    // ) -> Result<Vec<UnifiDeviceFull>, UnifiAPIError> {
-    //     let url =
-    //         format!("{}/api/s/{}/stat/device", self.server_url, site_code).into_boxed_str();
-    //     let resp = self.api_call(&url)?;
-    //     let site_unifi_devices_full: UnifiDevicesFullResp = simd_json::serde::from_reader(resp)
-    //         .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-    //     Ok(site_unifi_devices_full.data)
-    // }
-
-    // pub(crate) fn get_site_device_mac(
-    //     &mut self,
-    //     site_code: &str,
-    //     mac: &str,
-    // ) -> Result<Vec<UnifiDeviceFull>, UnifiAPIError> {
-    //     let url =
-    //         format!("{}/api/s/{}/stat/device/{}", self.server_url, site_code, mac).into_boxed_str();
-    //     let resp = self.api_call(&url)?;
-    //     let site_unifi_device_mac: UnifiDevicesFullResp = simd_json::serde::from_reader(resp)
-    //         .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-    //     Ok(site_unifi_device_mac.data)
-    // }
-
-    // pub(crate) fn get_site_clients_all(
-    //     &mut self,
-    //     site_code: &str,
-    // ) -> Result<Vec<ClientDevice>, UnifiAPIError> {
-    //     let url = format!("{}/api/s/{}/rest/user", self.server_url, site_code).into_boxed_str();
-    //     let resp = self.api_call(&url)?;
-    //     let site_client_devices_all: UnifiClientsAllResp = simd_json::serde::from_reader(resp)
-    //         .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-    //     Ok(site_client_devices_all.data)
-    // }
-
-    // pub(crate) fn get_site_clients_active(
-    //     &mut self,
-    //     site_code: &str,
-    // ) -> Result<Vec<ClientDeviceActive>, UnifiAPIError> {
-    //     let url = format!("{}/api/s/{}/stat/sta", self.server_url, site_code).into_boxed_str();
-    //     let resp = self.api_call(&url)?;
-    //     let site_client_devices_active: UnifiClientsActiveResp =
-    //         simd_json::serde::from_reader(resp)
-    //             .map_err(|source| UnifiAPIError::JsonError { url, source })?;
-    //     Ok(site_client_devices_active.data)
-    // }
+        self.runtime.block_on(self.client.get_site_devices_basic(site_code))
+    }
+
+    /// Every client the site has ever seen, `site_code` is `UnifiSite::code`
+    pub fn get_site_clients_all(
+        &self,
+        site_code: &str,
+    ) -> Result<Vec<ClientDevice>, UnifiAPIError> {
+        self.runtime.block_on(self.client.get_site_clients_all(site_code))
+    }
 }
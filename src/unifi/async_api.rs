@@ -0,0 +1,405 @@
+#![allow(dead_code)]
+
+use super::{
+    api::{UnifiAPIError, UnifiClientsAllResp, UnifiDevicesBasicResp, UnifiSelfResp, UnifiSitesResp, UnifiStatusResp},
+    builder::UnifiClientBuilder,
+    cancel::CancellationToken,
+    controller::ControllerInfo,
+    devices::{ClientDevice, UnifiDeviceBasic, UnifiSite},
+    server_url::ServerUrl,
+};
+use reqwest::{header::REFERER, Client, Response, StatusCode, Url};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    time::Duration,
+};
+use zeroize::Zeroize;
+
+/// Timeout of each request made while probing, shorter than the normal timeout since most ports won't respond
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Async client for a single Unifi controller, the session cookie is kept after logging in.
+/// Requests only need `&self`, so many sites can be requested concurrently with the same session.
+/// Clones share the session, so a clone can be kept for each task or thread
+#[derive(Debug, Clone)]
+pub struct AsyncUnifiClient {
+    client: Client,
+    /// As typed by the user, the port may still need to be probed
+    server_url: Arc<ServerUrl>,
+    /// Set once `probe_server_url` chooses the port, shared so every clone uses the chosen port
+    probed_server_url: Arc<OnceLock<ServerUrl>>,
+    is_logged_in: Arc<AtomicBool>,
+    /// Set by `login`, the Network application's API is behind `/proxy/network` on UniFi OS
+    is_unifi_os: Arc<AtomicBool>,
+    controller_info: Arc<OnceLock<ControllerInfo>>,
+    cancel_token: Option<CancellationToken>,
+}
+
+/// UniFi OS consoles answer the root URL directly, self-hosted controllers redirect to `/manage`
+fn is_unifi_os_response(requested_url: &Url, response: &Response) -> bool {
+    response.status().is_success() && response.url() == requested_url
+}
+
+impl AsyncUnifiClient {
+    /// `server_url` is the controller URL, ie `https://unifi.example.com:8443`.
+    /// Use `UnifiClient::builder` for any other settings
+    pub fn new(
+        server_url: &str,
+        accept_invalid_certs: bool,
+    ) -> Result<Self, UnifiAPIError> {
+        UnifiClientBuilder::new(server_url)
+            .accept_invalid_certs(accept_invalid_certs)
+            .build_async()
+    }
+
+    pub(super) fn from_parts(client: Client, server_url: ServerUrl) -> Self {
+        Self {
+            client,
+            server_url: Arc::new(server_url),
+            probed_server_url: Arc::new(OnceLock::new()),
+            is_logged_in: Arc::new(AtomicBool::new(false)),
+            is_unifi_os: Arc::new(AtomicBool::new(false)),
+            controller_info: Arc::new(OnceLock::new()),
+            cancel_token: None,
+        }
+    }
+
+    /// Controller URL without a trailing slash
+    pub fn server_url(&self) -> &Url {
+        self.resolved_server_url().url()
+    }
+
+    /// True if the port of the server URL was chosen by `probe_server_url`
+    pub fn server_url_was_probed(&self) -> bool {
+        self.probed_server_url.get().is_some()
+    }
+
+    /// The probed URL once `probe_server_url` chooses a port, otherwise the URL as typed
+    fn resolved_server_url(&self) -> &ServerUrl {
+        self.probed_server_url.get().unwrap_or(&self.server_url)
+    }
+
+    /// Full URL of an API endpoint, `path` starts with a slash
+    fn endpoint(&self, path: &str) -> Box<str> {
+        let prefix = match self.is_unifi_os() {
+            true => "/proxy/network",
+            false => "",
+        };
+        format!("{}{}{}", self.resolved_server_url(), prefix, path).into_boxed_str()
+    }
+
+    /// When the server URL doesn't include a port, each of `PROBE_PORTS` is tried in order.
+    /// The first port that responds like a Unifi controller is chosen, otherwise the first port that responds at all.
+    /// Every request after this uses the chosen port, including requests from clones of this client.
+    /// Returns true if a port was chosen, a client that was already probed isn't probed again
+    pub async fn probe_server_url(&self) -> Result<bool, UnifiAPIError> {
+        if self.server_url_was_probed() {
+            return Ok(true);
+        }
+        let candidates = self.server_url.candidates();
+        if candidates.len() == 1 {
+            return Ok(false);
+        }
+        let mut chosen_option = None;
+        let mut first_error_option = None;
+        for candidate in candidates {
+            match self.is_unifi_controller(&candidate).await {
+                Ok(true) => {
+                    chosen_option = Some(candidate);
+                    break;
+                }
+                Ok(false) => {
+                    chosen_option.get_or_insert(candidate);
+                }
+                Err(UnifiAPIError::Canceled) => return Err(UnifiAPIError::Canceled),
+                Err(e) => {
+                    first_error_option.get_or_insert(e);
+                }
+            }
+        }
+        match (chosen_option, first_error_option) {
+            (Some(chosen), _) => {
+                // a clone probing at the same time may have chosen first, either port responded
+                let _ = self.probed_server_url.set(ServerUrl::with_probed_port(chosen));
+                Ok(true)
+            }
+            (None, Some(first_error)) => Err(first_error),
+            (None, None) => unreachable!("there is more than one candidate"),
+        }
+    }
+
+    /// Self-hosted controllers answer `/status` without logging in, UniFi OS consoles answer the root URL
+    async fn is_unifi_controller(&self, candidate: &Url) -> Result<bool, UnifiAPIError> {
+        let base_url = candidate.as_str().trim_end_matches('/');
+        let request = async {
+            let status_response = self
+                .client
+                .get(format!("{}/status", base_url))
+                .timeout(PROBE_TIMEOUT)
+                .send()
+                .await?;
+            if status_response.status().is_success() {
+                let body = status_response.bytes().await?;
+                if body.windows(6).any(|window| window == b"\"meta\"") {
+                    return Ok(true);
+                }
+            }
+            let root_response = self.client.get(candidate.clone()).timeout(PROBE_TIMEOUT).send().await?;
+            Ok(is_unifi_os_response(candidate, &root_response))
+        };
+        self.cancelable(request)
+            .await?
+            .map_err(|source| UnifiAPIError::ReqwestError {
+                source
+            })
+    }
+
+    /// Requests are dropped as soon as the token is canceled & return `UnifiAPIError::Canceled`,
+    /// instead of waiting for the controller to respond or the request to time out
+    pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.cancel_token = Some(cancel_token);
+        self
+    }
+
+    /// Returns true once `login` succeeds
+    pub fn is_logged_in(&self) -> bool {
+        self.is_logged_in.load(Ordering::Relaxed)
+    }
+
+    /// True if the controller is a UniFi OS console, known once `login` succeeds
+    pub fn is_unifi_os(&self) -> bool {
+        self.is_unifi_os.load(Ordering::Relaxed)
+    }
+
+    /// The login endpoint & the API path depend on whether the controller is a UniFi OS console
+    async fn detect_unifi_os(&self) -> Result<bool, UnifiAPIError> {
+        let root_url = self.server_url();
+        let root_response = self
+            .cancelable(self.client.get(root_url.clone()).send())
+            .await?
+            .map_err(|source| UnifiAPIError::ReqwestError {
+                source
+            })?;
+        Ok(is_unifi_os_response(root_url, &root_response))
+    }
+
+    /// Drops the request once the cancellation token is canceled, which closes the connection
+    async fn cancelable<T>(&self, request: impl Future<Output = T>) -> Result<T, UnifiAPIError> {
+        match &self.cancel_token {
+            None => Ok(request.await),
+            Some(cancel_token) => tokio::select! {
+                biased;
+                _ = cancel_token.canceled() => Err(UnifiAPIError::Canceled),
+                result = request => Ok(result),
+            },
+        }
+    }
+
+    /// Logs in with `/api/login`, or `/api/auth/login` on UniFi OS.
+    /// The username & password are zeroized once the request is built
+    pub async fn login(
+        &self,
+        username: &mut str,
+        password: &mut str,
+    ) -> Result<(), UnifiAPIError> {
+        let is_unifi_os = match self.detect_unifi_os().await {
+            Ok(is_unifi_os) => is_unifi_os,
+            Err(e) => {
+                password.zeroize();
+                username.zeroize();
+                return Err(e);
+            }
+        };
+        self.is_unifi_os.store(is_unifi_os, Ordering::Relaxed);
+
+        let mut login_data: HashMap<&str, &mut str> = HashMap::new();
+        login_data.insert("username", username);
+        login_data.insert("password", password);
+
+        let url = match is_unifi_os {
+            true => format!("{}/api/auth/login", self.resolved_server_url()).into_boxed_str(),
+            false => self.endpoint("/api/login"),
+        };
+        let login_request = self
+            .client
+            .post(url.as_ref())
+            .header(REFERER, "/login")
+            .json(&login_data);
+
+        {
+            // zeroize the user entered data for security
+            login_data.iter_mut().for_each(|(_, v)| {
+                v.zeroize();
+            });
+            std::mem::drop(login_data);
+            password.zeroize();
+            username.zeroize();
+        };
+
+        let login_result = self.cancelable(login_request.send()).await?;
+        let login = {
+            let login_response = login_result
+                .map_err(|source| UnifiAPIError::ReqwestError {
+                    source,
+                })?;
+            // if controller returns HTTP 400, creds were bad, UniFi OS returns 401 or 403 instead
+            if matches!(login_response.status(), StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+                return Err(UnifiAPIError::LoginAuthenticationError { url });
+            }
+            login_response
+                .error_for_status()
+                .map_err(|source| UnifiAPIError::ReqwestError {
+                    source,
+                })?
+        };
+        if login.status().is_success() {
+            self.is_logged_in.store(true, Ordering::Relaxed);
+            Ok(())
+        } else {
+            Err(UnifiAPIError::LoginAuthenticationError { url })
+        }
+    }
+
+    /// Returns the body of the response, a canceled request stops even if the body is still downloading
+    async fn api_call(
+        &self,
+        url: &str,
+    ) -> Result<Vec<u8>, UnifiAPIError> {
+        let request = async {
+            self.client
+                .get(url)
+                .send()
+                .await
+                .and_then(Response::error_for_status)?
+                .bytes()
+                .await
+        };
+        let body = self
+            .cancelable(request)
+            .await?
+            .map_err(|source| UnifiAPIError::ReqwestError {
+                source
+            })?;
+        Ok(Vec::from(body))
+    }
+
+    /// Requests the version from `/status` & the account's permissions from `/api/self`.
+    /// The result is kept for `controller_info`, so later calls don't make any requests
+    pub async fn get_controller_info(&self) -> Result<ControllerInfo, UnifiAPIError> {
+        if let Some(controller_info) = self.controller_info.get() {
+            return Ok(controller_info.clone());
+        }
+
+        let status_url = self.endpoint("/status");
+        let version = match self.api_call(&status_url).await {
+            Ok(mut resp) => simd_json::serde::from_slice::<UnifiStatusResp>(&mut resp)
+                .ok()
+                .and_then(|status| status.meta.server_version),
+            Err(UnifiAPIError::Canceled) => return Err(UnifiAPIError::Canceled),
+            // the version is only informational, some reverse proxies don't pass `/status` through
+            Err(_) => None,
+        };
+
+        let url = self.endpoint("/api/self");
+        let mut resp = self.api_call(&url).await?;
+        let admin_self: UnifiSelfResp = simd_json::serde::from_slice(&mut resp)
+            .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+        let admin_option = admin_self.data.into_iter().next();
+
+        let controller_info = ControllerInfo {
+            version,
+            is_unifi_os: self.is_unifi_os(),
+            is_super_admin: admin_option.as_ref().is_some_and(|admin| admin.is_super),
+            is_read_only: admin_option.as_ref().is_some_and(|admin| admin.site_role.as_deref() == Some("readonly")),
+        };
+        Ok(self.controller_info.get_or_init(|| controller_info).clone())
+    }
+
+    /// The result of `get_controller_info`, if it was requested
+    pub fn controller_info(&self) -> Option<&ControllerInfo> {
+        self.controller_info.get()
+    }
+
+    /// Every site the user has access to
+    pub async fn get_sites(&self) -> Result<Vec<UnifiSite>, UnifiAPIError> {
+        let url = self.endpoint("/api/self/sites");
+        let mut resp = self.api_call(&url).await?;
+        let sites: UnifiSitesResp = simd_json::serde::from_slice(&mut resp)
+            .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+        Ok(sites.data)
+    }
+
+    /// Every site in the controller, including sites the user isn't assigned to. Only super admins are allowed to use this
+    pub async fn get_all_sites(&self) -> Result<Vec<UnifiSite>, UnifiAPIError> {
+        let url = self.endpoint("/api/stat/sites");
+        let mut resp = self.api_call(&url).await?;
+        let sites: UnifiSitesResp = simd_json::serde::from_slice(&mut resp)
+            .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+        Ok(sites.data)
+    }
+
+    /// Every device adopted to the site, `site_code` is `UnifiSite::code`
+    pub async fn get_site_devices_basic(
+        &self,
+        site_code: &str,
+    ) -> Result<Vec<UnifiDeviceBasic>, UnifiAPIError> {
+        let url = self.endpoint(&format!("/api/s/{}/stat/device-basic", site_code));
+        let mut resp = self.api_call(&url).await?;
+        let site_unifi_devices_basic: UnifiDevicesBasicResp =
+            simd_json::serde::from_slice(&mut resp)
+                .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+        Ok(site_unifi_devices_basic.data)
+    }
+
+    // pub async fn get_site_devices_full(
+    //     &self,
+    //     site_code: &str,
+    // ) -> Result<Vec<UnifiDeviceFull>, UnifiAPIError> {
+    //     let url = self.endpoint(&format!("/api/s/{}/stat/device", site_code));
+    //     let mut resp = self.api_call(&url).await?;
+    //     let site_unifi_devices_full: UnifiDevicesFullResp = simd_json::serde::from_slice(&mut resp)
+    //         .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+    //     Ok(site_unifi_devices_full.data)
+    // }
+
+    // pub async fn get_site_device_mac(
+    //     &self,
+    //     site_code: &str,
+    //     mac: &str,
+    // ) -> Result<Vec<UnifiDeviceFull>, UnifiAPIError> {
+    //     let url = self.endpoint(&format!("/api/s/{}/stat/device/{}", site_code, mac));
+    //     let mut resp = self.api_call(&url).await?;
+    //     let site_unifi_device_mac: UnifiDevicesFullResp = simd_json::serde::from_slice(&mut resp)
+    //         .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+    //     Ok(site_unifi_device_mac.data)
+    // }
+
+    /// Every client the site has ever seen, `site_code` is `UnifiSite::code`
+    pub async fn get_site_clients_all(
+        &self,
+        site_code: &str,
+    ) -> Result<Vec<ClientDevice>, UnifiAPIError> {
+        let url = self.endpoint(&format!("/api/s/{}/rest/user", site_code));
+        let mut resp = self.api_call(&url).await?;
+        let site_client_devices_all: UnifiClientsAllResp = simd_json::serde::from_slice(&mut resp)
+            .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+        Ok(site_client_devices_all.data)
+    }
+
+    // pub async fn get_site_clients_active(
+    //     &self,
+    //     site_code: &str,
+    // ) -> Result<Vec<ClientDeviceActive>, UnifiAPIError> {
+    //     let url = self.endpoint(&format!("/api/s/{}/stat/sta", site_code));
+    //     let mut resp = self.api_call(&url).await?;
+    //     let site_client_devices_active: UnifiClientsActiveResp =
+    //         simd_json::serde::from_slice(&mut resp)
+    //             .map_err(|source| UnifiAPIError::JsonError { url, source })?;
+    //     Ok(site_client_devices_active.data)
+    // }
+}
@@ -0,0 +1,94 @@
+use super::{
+    api::{UnifiAPIError, UnifiClient},
+    async_api::AsyncUnifiClient,
+    proxy::ProxyMode,
+    server_url::ServerUrl,
+};
+use reqwest::{Certificate, Client};
+use std::time::Duration;
+
+/// Sent with every request unless `UnifiClientBuilder::user_agent` is set
+pub const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// Timeout of each request unless `UnifiClientBuilder::timeout` is set
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Settings for a `UnifiClient` or `AsyncUnifiClient`, created with `UnifiClient::builder`
+#[derive(Debug)]
+pub struct UnifiClientBuilder {
+    server_url: Box<str>,
+    timeout: Duration,
+    accept_invalid_certs: bool,
+    root_certificates: Vec<Certificate>,
+    user_agent: Box<str>,
+    proxy: ProxyMode,
+}
+
+impl UnifiClientBuilder {
+    /// `server_url` is the controller URL, ie `https://unifi.example.com:8443`, it's normalized by `ServerUrl::parse`
+    pub fn new(server_url: &str) -> Self {
+        Self {
+            server_url: Box::from(server_url),
+            timeout: DEFAULT_TIMEOUT,
+            accept_invalid_certs: false,
+            root_certificates: Vec::new(),
+            user_agent: Box::from(DEFAULT_USER_AGENT),
+            proxy: ProxyMode::System,
+        }
+    }
+
+    /// Timeout of each request, from connecting until the body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Accept invalid HTTPS certificates, ie self-signed certificates
+    pub fn accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Trust a certificate in addition to the system's root certificates, ie the controller's self-signed certificate
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Box::from(user_agent);
+        self
+    }
+
+    /// The proxy environment variables are used unless this is set
+    pub fn proxy(mut self, proxy: ProxyMode) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    pub fn build_async(self) -> Result<AsyncUnifiClient, UnifiAPIError> {
+        let server_url = ServerUrl::parse(&self.server_url)?;
+        let mut client_builder = Client::builder()
+            .timeout(self.timeout)
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .user_agent(self.user_agent.as_ref())
+            .cookie_store(true);
+        for certificate in self.root_certificates {
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
+        client_builder = match self.proxy {
+            // reqwest reads the environment variables by default
+            ProxyMode::System => client_builder,
+            ProxyMode::Disabled => client_builder.no_proxy(),
+            ProxyMode::Custom(proxy_config) => client_builder.proxy(proxy_config.to_reqwest_proxy()?),
+        };
+        let client = client_builder
+            .build()
+            .map_err(|source| UnifiAPIError::ClientError{ source })?;
+        Ok(AsyncUnifiClient::from_parts(client, server_url))
+    }
+
+    /// The blocking client runs the async client on its own runtime
+    pub fn build(self) -> Result<UnifiClient, UnifiAPIError> {
+        UnifiClient::from_async(self.build_async()?)
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::Notify;
+
+/// Cancels a running search from another thread. Clones share the same state,
+/// so keep one clone & pass the other to the search
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    is_canceled: AtomicBool,
+    /// Wakes the tasks waiting in `canceled`
+    notify: Notify,
+}
+
+impl CancellationToken {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The request in progress is dropped & the search returns no matches
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.is_canceled.store(true, Ordering::Relaxed);
+        self.0.notify.notify_waiters();
+    }
+
+    #[inline]
+    pub fn is_canceled(&self) -> bool {
+        self.0.is_canceled.load(Ordering::Relaxed)
+    }
+
+    /// Completes once the token is canceled, for use in `tokio::select!`
+    pub async fn canceled(&self) {
+        loop {
+            // created before checking the flag, so a `cancel` in between still wakes it
+            let notified = self.0.notify.notified();
+            if self.is_canceled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+/// Version of the Network application & what the logged in account is allowed to do,
+/// from `UnifiClient::get_controller_info`
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ControllerInfo {
+    /// Network application version, ie `8.0.24`, `None` if the controller doesn't report it
+    pub version: Option<Box<str>>,
+    /// The Network application runs on a UniFi OS console (UDM, Cloud Key Gen2, etc.)
+    pub is_unifi_os: bool,
+    /// Super admins can see & manage every site in the controller
+    pub is_super_admin: bool,
+    /// The account's role on the default site is read-only.
+    /// Only informational, every request this tool makes is allowed for read-only admins so nothing is disabled by it
+    pub is_read_only: bool,
+}
+
+impl ControllerInfo {
+    /// Only super admins are allowed to list every site, including sites they aren't assigned to
+    #[inline]
+    pub fn can_list_all_sites(&self) -> bool {
+        self.is_super_admin
+    }
+}
+
+impl std::fmt::Display for ControllerInfo {
+    /// ie `Network 8.0.24 on UniFi OS, super admin`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "Network {}", version)?,
+            None => f.write_str("Network (unknown version)")?,
+        }
+        if self.is_unifi_os {
+            f.write_str(" on UniFi OS")?;
+        }
+        let role = match (self.is_super_admin, self.is_read_only) {
+            (true, _) => "super admin",
+            (false, true) => "read-only admin",
+            (false, false) => "site admin",
+        };
+        write!(f, ", {}", role)
+    }
+}
+
+/// The logged in admin, from `/api/self`
+#[derive(Default, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(super) struct UnifiAdmin {
+    pub(super) is_super: bool,
+    /// Role on the current site, `admin` or `readonly`
+    pub(super) site_role: Option<Box<str>>,
+}
+
+/// Only the version is needed from `/status`, which doesn't require logging in
+#[derive(Default, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(super) struct UnifiStatusMeta {
+    pub(super) server_version: Option<Box<str>>,
+}
@@ -1,3 +1,26 @@
-pub(crate) mod api;
-pub(crate) mod devices;
-pub(crate) mod search;
+/// Blocking client for the Unifi controller API
+pub mod api;
+/// Async client for the Unifi controller API, built on tokio
+pub mod async_api;
+/// Settings for the blocking & async clients
+pub mod builder;
+/// Cancelling a running search from another thread
+pub mod cancel;
+/// Version & permissions of the controller
+pub mod controller;
+/// Sites, devices, & clients returned by the Unifi controller API
+pub mod devices;
+/// Settings saved for each controller
+pub mod profiles;
+/// Progress events reported while searching
+pub mod progress;
+/// Proxy settings for the clients
+pub mod proxy;
+/// Searching every site in a controller by MAC Address
+pub mod search;
+/// Normalizing the controller URL entered by the user
+pub mod server_url;
+/// Choosing which sites are searched with globs & regexes
+pub mod site_filter;
+/// Device models without a known label, recorded while searching
+pub mod unknown_models;
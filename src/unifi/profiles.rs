@@ -0,0 +1,168 @@
+use super::{server_url::ServerUrl, site_filter::SiteFilter, unknown_models::data_file_path};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+use thiserror::Error;
+
+const PROFILES_FILE_NAME: &str = "controller_profiles.json";
+
+/// Error reading or writing the controller profiles file
+#[derive(Error, Debug)]
+pub enum ProfilesError {
+    #[error("Unable to access file {path}\n{source}")]
+    Io {
+        path: Box<str>,
+        source: io::Error,
+    },
+    #[error("Unable to parse file {path}\n{source}")]
+    Json {
+        path: Box<str>,
+        source: serde_json::Error,
+    },
+}
+
+/// Settings saved for a single controller, passwords are never saved
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControllerProfile {
+    /// Normalized server URL, see `ControllerProfiles::profile_key`
+    pub server_url: Box<str>,
+    #[serde(default)]
+    pub site_filter: SiteFilter,
+    /// Set once the controller is saved to be searched with others, a profile holding only a site filter has no name
+    #[serde(default, skip_serializing_if = "str::is_empty")]
+    pub name: Box<str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<Box<str>>,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// Environment variable the command line interface reads the password from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<Box<str>>,
+}
+
+impl ControllerProfile {
+    /// Saved controllers have a name & can be searched together
+    #[inline]
+    pub fn is_saved_controller(&self) -> bool {
+        !self.name.is_empty()
+    }
+}
+
+/// Persistent settings for each controller, stored next to the executable
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControllerProfiles {
+    profiles: Vec<ControllerProfile>,
+}
+
+impl ControllerProfiles {
+    /// Location of the profiles; falls back to the working directory if the executable path is unavailable
+    pub fn file_path() -> PathBuf {
+        data_file_path(PROFILES_FILE_NAME)
+    }
+
+    /// Loads the profiles, no profiles are returned if the file doesn't exist yet
+    pub fn load() -> Result<Self, ProfilesError> {
+        let path = Self::file_path();
+        match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|source| ProfilesError::Json {
+                path: path.display().to_string().into_boxed_str(),
+                source,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(ProfilesError::Io {
+                path: path.display().to_string().into_boxed_str(),
+                source,
+            }),
+        }
+    }
+
+    /// Overwrites `controller_profiles.json` with every profile
+    pub fn save(&self) -> Result<(), ProfilesError> {
+        let path = Self::file_path();
+        let json = serde_json::to_vec_pretty(self).map_err(|source| ProfilesError::Json {
+            path: path.display().to_string().into_boxed_str(),
+            source,
+        })?;
+        fs::write(&path, json).map_err(|source| ProfilesError::Io {
+            path: path.display().to_string().into_boxed_str(),
+            source,
+        })
+    }
+
+    /// Profiles are found by the normalized server URL, so `unifi.local` & `https://unifi.local/` share a profile
+    pub fn profile_key(server_url: &str) -> Box<str> {
+        match ServerUrl::parse(server_url) {
+            Ok(server_url) => server_url.to_string().into_boxed_str(),
+            Err(_) => Box::from(server_url.trim()),
+        }
+    }
+
+    #[inline]
+    pub fn profiles(&self) -> &[ControllerProfile] {
+        &self.profiles
+    }
+
+    pub fn get(&self, server_url: &str) -> Option<&ControllerProfile> {
+        let key = Self::profile_key(server_url);
+        self.profiles.iter().find(|profile| profile.server_url == key)
+    }
+
+    /// The profile for the server URL, an empty profile is added if there isn't one
+    pub fn get_or_insert(&mut self, server_url: &str) -> &mut ControllerProfile {
+        let key = Self::profile_key(server_url);
+        match self.profiles.iter().position(|profile| profile.server_url == key) {
+            Some(i) => &mut self.profiles[i],
+            None => {
+                self.profiles.push(ControllerProfile {
+                    server_url: key,
+                    ..Default::default()
+                });
+                self.profiles.last_mut().expect("a profile was just added")
+            }
+        }
+    }
+
+    /// Profiles of the controllers saved to be searched together, in the order they were saved
+    pub fn controllers(&self) -> impl Iterator<Item = &ControllerProfile> {
+        self.profiles.iter().filter(|profile| profile.is_saved_controller())
+    }
+
+    /// A saved controller by name, ignoring case
+    pub fn controller(&self, name: &str) -> Option<&ControllerProfile> {
+        self.controllers().find(|profile| profile.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The profile for the server URL, named so it's a saved controller.
+    /// A controller saved with the same name for another server URL is removed
+    pub fn save_controller(&mut self, name: &str, server_url: &str) -> &mut ControllerProfile {
+        let key = Self::profile_key(server_url);
+        let name = name.trim();
+        if self.controller(name).is_some_and(|profile| profile.server_url != key) {
+            self.remove_controller(name);
+        }
+        let profile = self.get_or_insert(&key);
+        profile.name = Box::from(name);
+        profile
+    }
+
+    /// Forgets the controller's name & account, its site filter is kept. Returns false if there's no such controller
+    pub fn remove_controller(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        match self.profiles.iter_mut().find(|profile| profile.is_saved_controller() && profile.name.eq_ignore_ascii_case(name)) {
+            Some(profile) => {
+                profile.name = Box::default();
+                profile.username = None;
+                profile.accept_invalid_certs = false;
+                profile.password_env = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The saved site filter for the server URL, an empty filter if there's no profile
+    pub fn site_filter(&self, server_url: &str) -> SiteFilter {
+        self.get(server_url)
+            .map(|profile| profile.site_filter.clone())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,62 @@
+use super::{
+    api::UnifiAPIError,
+    controller::ControllerInfo,
+    devices::{ClientDevice, UnifiDeviceBasic, UnifiSite},
+};
+use reqwest::Url;
+
+/// Something that happened while a search was running, in the order they happen
+#[derive(Debug)]
+pub enum SearchEvent<'a> {
+    /// A controller is about to be logged in to, `index` starts at 0 & `count` is the number of controllers searched.
+    /// The events that follow are for this controller until it's finished or failed
+    ControllerStarted { name: &'a str, index: usize, count: usize },
+    /// The controller URL every request is sent to, `probed` is true if the port was chosen by probing
+    ServerUrlResolved { url: &'a Url, probed: bool },
+    /// Logged in to the controller
+    LoggedIn,
+    /// The version of the controller & the account's permissions, only sent if they could be requested
+    ControllerIdentified(&'a ControllerInfo),
+    /// Number of sites that will be searched
+    SitesListed(usize),
+    /// The devices (& clients) of the site are about to be requested
+    SiteStarted(&'a UnifiSite),
+    /// The site was searched, with the number of devices in the site
+    SiteFinished(&'a UnifiSite, usize),
+    /// A device in the site doesn't have a known label, sent each time its site is searched.
+    /// Record it with `UnknownModels::record_event`
+    UnknownModel { site: &'a UnifiSite, device: &'a UnifiDeviceBasic },
+    /// A request for the site failed with a network error & is being retried, `attempt` starts at 1
+    Retrying {
+        site: &'a UnifiSite,
+        attempt: u32,
+        error: &'a UnifiAPIError,
+    },
+    /// A device or client matched the search, reported as soon as it's found
+    Match(SearchMatch<'a>),
+    /// Every site in the controller was searched, or an exact search found a match
+    ControllerFinished { name: &'a str },
+    /// The controller couldn't be searched & was skipped, only sent when several controllers are searched
+    ControllerFailed { name: &'a str, error: &'a UnifiAPIError },
+}
+
+/// A device or client that matched the search
+#[derive(Debug, Clone, Copy)]
+pub enum SearchMatch<'a> {
+    Device(&'a UnifiDeviceBasic),
+    Client(&'a ClientDevice),
+}
+
+/// Receives progress events from a running search.
+///
+/// Implemented for closures, so `|event: &SearchEvent| println!("{:?}", event)` can be passed directly
+pub trait SearchObserver {
+    fn on_event(&mut self, event: &SearchEvent<'_>);
+}
+
+impl<F: FnMut(&SearchEvent<'_>)> SearchObserver for F {
+    #[inline]
+    fn on_event(&mut self, event: &SearchEvent<'_>) {
+        self(event)
+    }
+}
@@ -0,0 +1,73 @@
+use super::api::UnifiAPIError;
+use reqwest::{NoProxy, Proxy};
+
+/// Which proxy the client's requests are sent through
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ProxyMode {
+    /// The proxy from the `HTTPS_PROXY`, `HTTP_PROXY`, or `ALL_PROXY` environment variables,
+    /// except for hosts in `NO_PROXY`
+    #[default]
+    System,
+    /// Connect directly, the environment variables are ignored
+    Disabled,
+    Custom(ProxyConfig),
+}
+
+/// A proxy every request is sent through, hosts in the `NO_PROXY` environment variable are still connected to directly
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// `http://` or `https://` for an HTTP CONNECT proxy, `socks5://` for a SOCKS5 proxy,
+    /// or `socks5h://` for a SOCKS5 proxy that also resolves the controller's hostname,
+    /// ie `socks5h://jump.example.com:1080`
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    pub const SCHEMES: [&'static str; 4] = ["http", "https", "socks5", "socks5h"];
+
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: String::from(url),
+            ..Default::default()
+        }
+    }
+
+    /// Authenticate with the proxy, using basic auth for HTTP proxies
+    pub fn with_auth(mut self, username: &str, password: &str) -> Self {
+        self.username = Some(String::from(username));
+        self.password = Some(String::from(password));
+        self
+    }
+
+    pub(super) fn to_reqwest_proxy(&self) -> Result<Proxy, UnifiAPIError> {
+        let invalid_proxy = |reason: String| UnifiAPIError::InvalidProxy {
+            url: Box::from(self.url.as_str()),
+            reason: reason.into_boxed_str(),
+        };
+        let scheme = self.url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+        if !scheme.is_some_and(|scheme| ProxyConfig::SCHEMES.contains(&scheme.as_str())) {
+            return Err(invalid_proxy(format!(
+                "the URL must start with {}://",
+                ProxyConfig::SCHEMES.join("://, ")
+            )));
+        }
+        let mut proxy = Proxy::all(self.url.as_str()).map_err(|e| invalid_proxy(e.to_string()))?;
+        if let Some(username) = self.username.as_deref().filter(|username| !username.is_empty()) {
+            proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or_default());
+        }
+        Ok(proxy.no_proxy(NoProxy::from_env()))
+    }
+}
+
+/// The password is never printed
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "********"))
+            .finish()
+    }
+}
@@ -1,38 +1,297 @@
 use crate::{
-    gui::{CancelSignal, ChannelsSearchThread},
-    mac_address::MacAddress,
+    mac_address::{parse_ipv6, validation::text_is_valid_mac, MacAddress, MacMask, MacPattern},
     unifi::{
         api::{UnifiAPIError, UnifiClient},
-        devices::UnifiDeviceBasic,
+        builder::UnifiClientBuilder,
+        cancel::CancellationToken,
+        devices::{ClientDevice, DeviceType, UnifiDeviceBasic, UnifiSite},
+        progress::{SearchEvent, SearchMatch, SearchObserver},
+        proxy::ProxyMode,
+        site_filter::SiteFilter,
     },
 };
 use multiversion::multiversion;
+use std::{thread, time::Duration};
 use zeroize::Zeroize;
 
+/// A controller to search & the account to log in with, the username & password are zeroized after logging in
 #[derive(Default, Debug, Clone)]
-pub struct UnifiSearchInfo {
+pub struct UnifiController {
+    /// Shown with each result & in the progress, the server URL is shown if it's empty
+    pub name: String,
     pub username: String,
     pub password: String,
+    /// Controller URL without a trailing slash, ie `https://unifi.example.com:8443`
     pub server_url: String,
-    pub mac_to_search: MacAddress,
     pub accept_invalid_certs: bool,
+    /// Sites left out of the search, applied after the sites are listed
+    pub site_filter: SiteFilter,
+}
+
+impl UnifiController {
+    /// The name, or the server URL if the controller wasn't named
+    #[inline]
+    pub fn display_name(&self) -> &str {
+        match self.name.trim() {
+            "" => self.server_url.trim(),
+            name => name,
+        }
+    }
+}
+
+/// Everything needed to run a search, the usernames & passwords are zeroized once the search finishes
+#[derive(Default, Debug, Clone)]
+pub struct UnifiSearchInfo {
+    /// Searched one after another, an exact search stops at the first controller with a match
+    pub controllers: Vec<UnifiController>,
+    pub mac_query: MacQuery,
+    /// Only devices of this type are matched, clients are skipped
+    pub device_type_filter: Option<DeviceType>,
+    /// Also search the clients known to each site
+    pub search_clients: bool,
+    pub proxy: ProxyMode,
+    /// Search every site in each controller, including sites the account isn't assigned to.
+    /// Only super admins can, for other accounts only their own sites are searched
+    pub all_sites: bool,
+}
+
+/// What to match the MAC Address of each device against
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacQuery {
+    /// Stops at the first device found
+    Exact(MacAddress),
+    /// Every matching device across all sites is returned
+    Pattern(MacPattern),
+    /// Every device in the range across all sites is returned
+    Mask(MacMask),
+    /// Every device matching any of the MAC Addresses across all sites is returned, sorted for a binary search
+    List(Box<[MacAddress]>),
+    /// Every device with a MAC Address within `max_offset` of `base` is returned,
+    /// since the MAC Address of an AP's radio or a switch port is offset from the management MAC Address
+    Nearby { base: MacAddress, max_offset: u8 },
+}
+
+impl MacQuery {
+    #[inline]
+    fn matches(&self, mac: MacAddress) -> bool {
+        match self {
+            MacQuery::Exact(mac_to_search) => mac == *mac_to_search,
+            MacQuery::Pattern(mac_pattern) => mac_pattern.matches(mac),
+            MacQuery::Mask(mac_mask) => mac_mask.contains(mac),
+            MacQuery::List(macs) => macs.binary_search(&mac).is_ok(),
+            MacQuery::Nearby { base, max_offset } => mac.distance(*base) <= u64::from(*max_offset),
+        }
+    }
+
+    /// How a matched MAC Address matched this query, a nearby search with an offset of 0 is an exact match
+    #[inline]
+    pub fn match_type(&self, mac: MacAddress) -> MatchType {
+        match self {
+            MacQuery::Exact(_) | MacQuery::List(_) => MatchType::Exact,
+            MacQuery::Pattern(_) => MatchType::Pattern,
+            MacQuery::Mask(_) => MatchType::Range,
+            MacQuery::Nearby { base, .. } if *base == mac => MatchType::Exact,
+            MacQuery::Nearby { .. } => MatchType::Nearby,
+        }
+    }
+
+    /// Offset of a matched MAC Address from the searched MAC Address, only for nearby searches
+    #[inline]
+    pub fn offset_of(&self, mac: MacAddress) -> Option<i64> {
+        match self {
+            MacQuery::Nearby { base, .. } => Some(mac.offset_from(*base)),
+            _ => None,
+        }
+    }
+
+    /// Parses the MAC Address entered by the user as a full MAC Address, a range if it has a prefix length,
+    /// or a partial MAC Address if it contains wildcards or is only the last 6 or more hex digits.
+    /// The MAC Address is extracted from SLAAC IPv6 addresses.
+    /// A nearby range is only allowed with a full MAC Address
+    pub fn parse(mac_addr_input: &str, nearby_range: u8) -> Result<MacQuery, Box<str>> {
+        let mac_to_search = if let Some(ipv6_addr) = parse_ipv6(mac_addr_input) {
+            MacAddress::from_ipv6(ipv6_addr).ok_or_else(|| {
+                format!(
+                    "IPv6 address {} wasn't derived from a MAC Address. Only SLAAC addresses with an EUI-64 interface identifier (ff:fe in the middle) contain a MAC Address, privacy & DHCPv6 addresses don't.",
+                    ipv6_addr
+                ).into_boxed_str()
+            })?
+        } else if MacMask::is_mask(mac_addr_input) || MacPattern::is_pattern(mac_addr_input) {
+            if nearby_range != 0 {
+                return Err(Box::from("Nearby MACs can only be used with a full MAC Address, set it to 0 to search for a partial MAC Address or range"));
+            }
+            let mac_query = match MacMask::is_mask(mac_addr_input) {
+                true => mac_addr_input.parse().map(MacQuery::Mask),
+                false => mac_addr_input.parse().map(MacQuery::Pattern),
+            };
+            return mac_query.map_err(|e| e.to_string().into_boxed_str());
+        } else if text_is_valid_mac(mac_addr_input.as_bytes()) {
+            MacAddress::try_from(mac_addr_input)
+                .expect("Mac Address validation failed") // SAFETY: this should never error due to the check above
+        } else {
+            return Err(Box::from("MAC Address must be formatted like XX:XX:XX:XX:XX:XX, XX-XX-XX-XX-XX-XX, XX XX XX XX XX XX, XXXX.XXXX.XXXX, or XXXXXXXXXXXX with hexadecimal characters only.\nFor a partial MAC Address, use `?` for a single unknown character or `*` for any number of unknown characters, like *:3F:A2:10, or enter at least the last 6 digits, like 3FA210\nFor a range of MAC Addresses, add a prefix length, like 74:83:C2:00:00:00/24\nA SLAAC IPv6 address, like fe80::7683:c2ff:fe3f:a210, can also be entered"));
+        };
+
+        match nearby_range {
+            0 => Ok(MacQuery::Exact(mac_to_search)),
+            max_offset => Ok(MacQuery::Nearby { base: mac_to_search, max_offset }),
+        }
+    }
+
+    /// Searches for multiple MAC Addresses at once
+    pub fn from_list(mut macs: Vec<MacAddress>) -> Self {
+        macs.sort_unstable();
+        macs.dedup();
+        MacQuery::List(macs.into_boxed_slice())
+    }
+
+    /// An exact search stops at the first match, other searches return all matches across all sites
+    #[inline]
+    fn is_exact(&self) -> bool {
+        matches!(self, MacQuery::Exact(_))
+    }
+}
+
+/// How a MAC Address matched the search
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MatchType {
+    Exact,
+    Pattern,
+    Range,
+    Nearby,
+}
+
+impl MatchType {
+    /// Lowercase name, ie `nearby`
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MatchType::Exact   => "exact",
+            MatchType::Pattern => "pattern",
+            MatchType::Range   => "range",
+            MatchType::Nearby  => "nearby",
+        }
+    }
 }
 
-pub type UnifiSearchResult = Result<Option<UnifiDeviceBasic>, UnifiAPIError>;
+impl Default for MacQuery {
+    fn default() -> Self {
+        MacQuery::Exact(MacAddress::default())
+    }
+}
+
+impl std::fmt::Display for MacQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MacQuery::Exact(mac) => mac.fmt(f),
+            MacQuery::Pattern(mac_pattern) => mac_pattern.fmt(f),
+            MacQuery::Mask(mac_mask) => mac_mask.fmt(f),
+            MacQuery::List(macs) => write!(f, "{} MAC Addresses", macs.len()),
+            MacQuery::Nearby { base, max_offset } => write!(f, "{} ±{}", base, max_offset),
+        }
+    }
+}
+
+/// Every Unifi device & client that matched the search
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UnifiSearchMatches {
+    pub devices: Vec<UnifiDeviceBasic>,
+    pub clients: Vec<ClientDevice>,
+    /// Added up across every controller searched
+    pub sites: SiteCoverage,
+    /// Controllers that couldn't be searched, only when several controllers are searched
+    pub failed_controllers: Vec<FailedController>,
+}
+
+/// A controller that was skipped because of an error, the other controllers are still searched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedController {
+    /// `UnifiController::display_name`
+    pub name: Box<str>,
+    pub error: Box<str>,
+}
+
+/// How many of the controller's sites were searched,
+/// so a device that wasn't found can be told apart from a device in a site the account can't see
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SiteCoverage {
+    /// Sites searched, an exact search stops at the site the device is found in
+    pub searched: usize,
+    /// Every site in the controller, only known when the account is a super admin
+    pub total: Option<usize>,
+    /// Sites left out by the site filter
+    pub excluded: usize,
+}
+
+impl SiteCoverage {
+    /// Sites in the controller that weren't searched & weren't left out by the site filter, `None` if the total isn't known
+    #[inline]
+    pub fn not_searched(&self) -> Option<usize> {
+        self.total.map(|total| total.saturating_sub(self.searched + self.excluded))
+    }
+
+    /// Adds the sites of another controller, the total is only known if it's known for both
+    #[inline]
+    fn add(&mut self, other: SiteCoverage) {
+        self.searched += other.searched;
+        self.total = self.total.zip(other.total).map(|(total, other_total)| total + other_total);
+        self.excluded += other.excluded;
+    }
+}
+
+impl std::fmt::Display for SiteCoverage {
+    /// ie `3 of 5 sites`, or `3 sites` if the total isn't known, with the number of sites left out by the site filter
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{} of {} sites", self.searched, total)?,
+            None => write!(f, "{} sites", self.searched)?,
+        }
+        match self.excluded {
+            0 => Ok(()),
+            excluded => write!(f, " ({} skipped by the site filter)", excluded),
+        }
+    }
+}
+
+impl UnifiSearchMatches {
+    /// No matches means nothing was found or the search was canceled
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty() && self.clients.is_empty()
+    }
+}
+
+/// Result of a search, the matches are empty if nothing was found or the search was canceled
+pub type UnifiSearchResult = Result<UnifiSearchMatches, UnifiAPIError>;
 
 // #[derive(Debug, Clone)]
-// pub(crate) enum UnifiSearchType {
+// pub enum UnifiSearchType {
 //     NetworkDevice,
 //     ClientDevice
 // }
 
-fn get_client_and_login<'a>(
+/// Creates a client, probes for the port if the server URL doesn't have one, & logs in.
+/// The username & password are zeroized even if the login fails.
+/// Every request made with the client is abandoned once `cancel_token` is canceled
+pub fn get_client_and_login(
+    client_builder: UnifiClientBuilder,
     username: &mut str,
     password: &mut str,
-    server_url: &'a str,
-    accept_invalid_certs: bool,
-) -> Result<UnifiClient<'a>, UnifiAPIError> {
-    let mut client = UnifiClient::new(server_url, accept_invalid_certs)?;
+    cancel_token: &CancellationToken,
+) -> Result<UnifiClient, UnifiAPIError> {
+    let client_result = client_builder.build().and_then(|client| {
+        let client = client.with_cancel_token(cancel_token.clone());
+        client.probe_server_url()?;
+        Ok(client)
+    });
+    let client = match client_result {
+        Ok(client) => client,
+        Err(e) => {
+            password.zeroize();
+            username.zeroize();
+            return Err(e);
+        }
+    };
     let login_result = client.login(username, password);
 
     // zeroize the user entered data for security
@@ -46,67 +305,318 @@ fn get_client_and_login<'a>(
     Ok(client)
 }
 
+/// The sites the account is assigned to, or every site if `all_sites` is set & the account is a super admin.
+/// Also returns the number of sites in the controller, which only super admins can see
+pub fn get_sites_to_search(
+    client: &UnifiClient,
+    all_sites: bool,
+) -> Result<(Vec<UnifiSite>, Option<usize>), UnifiAPIError> {
+    let can_list_all_sites = match client.get_controller_info() {
+        Ok(controller_info) => controller_info.can_list_all_sites(),
+        Err(UnifiAPIError::Canceled) => return Err(UnifiAPIError::Canceled),
+        Err(_) => false,
+    };
+    if !can_list_all_sites {
+        return Ok((client.get_sites()?, None));
+    }
+    let every_site = client.get_all_sites()?;
+    let total = every_site.len();
+    match all_sites {
+        true => Ok((every_site, Some(total))),
+        false => Ok((client.get_sites()?, Some(total))),
+    }
+}
+
+#[multiversion(targets = "simd")]
+fn filter_devices_simd(
+    site_devices: Vec<UnifiDeviceBasic>,
+    mac_query: &MacQuery,
+    device_type_filter: Option<&DeviceType>,
+) -> Vec<UnifiDeviceBasic> {
+    site_devices.into_iter().filter(|device| {
+        mac_query.matches(device.mac)
+            && device_type_filter.is_none_or(|device_type| device.device_type == *device_type)
+    }).collect()
+}
+
 #[multiversion(targets = "simd")]
-fn find_device_simd(site_devices: Vec<UnifiDeviceBasic>, mac_to_search: MacAddress) -> Option<UnifiDeviceBasic> {
-    site_devices.into_iter().find(|device| {
-        device.mac == mac_to_search
-    })
+fn filter_clients_simd(site_clients: Vec<ClientDevice>, mac_query: &MacQuery) -> Vec<ClientDevice> {
+    site_clients.into_iter().filter(|client| {
+        mac_query.matches(client.mac)
+    }).collect()
 }
 
+/// Number of times a request for a single site is retried after a network error
+const SITE_REQUEST_RETRIES: u32 = 2;
+/// Delay before retrying, multiplied by the attempt number
+const SITE_REQUEST_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Timeouts, failed connections, & server errors may succeed if tried again, anything else won't
+fn is_transient(error: &UnifiAPIError) -> bool {
+    match error {
+        UnifiAPIError::ReqwestError { source } => {
+            source.is_timeout()
+                || source.is_connect()
+                || source.status().is_some_and(|status| status.is_server_error())
+        }
+        _ => false,
+    }
+}
+
+/// Sleeps in short steps so a cancellation isn't delayed by the full duration
+fn sleep_unless_canceled(duration: Duration, cancel_token: &CancellationToken) {
+    const STEP: Duration = Duration::from_millis(50);
+    let mut remaining = duration;
+    while !remaining.is_zero() && !cancel_token.is_canceled() {
+        let step = remaining.min(STEP);
+        thread::sleep(step);
+        remaining -= step;
+    }
+}
+
+/// Runs a request for the site, retrying transient network errors unless the search was canceled
+fn with_retries<T>(
+    site: &UnifiSite,
+    observer: &mut dyn SearchObserver,
+    cancel_token: &CancellationToken,
+    mut request: impl FnMut() -> Result<T, UnifiAPIError>,
+) -> Result<T, UnifiAPIError> {
+    let mut attempt = 0;
+    loop {
+        match request() {
+            Err(error) if attempt < SITE_REQUEST_RETRIES && is_transient(&error) && !cancel_token.is_canceled() => {
+                attempt += 1;
+                observer.on_event(&SearchEvent::Retrying { site, attempt, error: &error });
+                sleep_unless_canceled(SITE_REQUEST_RETRY_DELAY * attempt, cancel_token);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Searches every site in each controller for devices (& optionally clients) matching the MAC Address query.
+///
+/// `observer` is sent a `SearchEvent` as the search progresses, including each match as it's found.
+/// Once `cancel_token` is canceled, the request in progress is abandoned & the search returns no matches.
+/// When several controllers are searched, a controller that fails is recorded in `failed_controllers`
+/// & the search continues, an error is only returned if every controller failed.
+/// Devices without a known label are sent to `observer` as `SearchEvent::UnknownModel`, nothing is written to disk.
 pub fn find_unifi_device(
     search_info: &mut UnifiSearchInfo,
-    search_thread_channels: &mut ChannelsSearchThread,
+    mut observer: impl SearchObserver,
+    cancel_token: &CancellationToken,
+) -> UnifiSearchResult {
+    let unifi_search_result = match search_all_controllers(search_info, &mut observer, cancel_token) {
+        Err(UnifiAPIError::Canceled) => Ok(UnifiSearchMatches::default()),
+        unifi_search_result => unifi_search_result,
+    };
+    // controllers after an exact match or a cancellation are never logged in to
+    for controller in search_info.controllers.iter_mut() {
+        controller.password.zeroize();
+        controller.username.zeroize();
+    }
+    unifi_search_result
+}
+
+/// Runs a search on the current thread without reporting progress or checking for cancellation
+pub fn find_unifi_device_headless(search_info: &mut UnifiSearchInfo) -> UnifiSearchResult {
+    find_unifi_device(search_info, |_: &SearchEvent| {}, &CancellationToken::new())
+}
+
+fn search_all_controllers(
+    search_info: &mut UnifiSearchInfo,
+    observer: &mut dyn SearchObserver,
+    cancel_token: &CancellationToken,
 ) -> UnifiSearchResult {
     let UnifiSearchInfo {
+        controllers,
+        ref mac_query,
+        ref device_type_filter,
+        ref search_clients,
+        ref proxy,
+        ref all_sites,
+    } = search_info;
+
+    let controller_count = controllers.len();
+    let mut search_matches = UnifiSearchMatches {
+        // each controller adds its sites once they're listed, an unknown total makes the sum unknown
+        sites: SiteCoverage {
+            total: Some(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let search_query = SearchQuery {
+        mac_query,
+        device_type_filter: device_type_filter.as_ref(),
+        search_clients: *search_clients,
+        proxy,
+        all_sites: *all_sites,
+    };
+    let mut first_error_option = None;
+    for (index, controller) in controllers.iter_mut().enumerate() {
+        if cancel_token.is_canceled() {
+            return Ok(UnifiSearchMatches::default());
+        }
+        // results are only tagged with the controller when there's more than one to tell apart
+        let controller_name: Box<str> = match controller_count {
+            1 => Box::default(),
+            _ => Box::from(controller.display_name()),
+        };
+        observer.on_event(&SearchEvent::ControllerStarted {
+            name: controller.display_name(),
+            index,
+            count: controller_count,
+        });
+        let controller_result = search_controller(
+            controller,
+            &controller_name,
+            &search_query,
+            &mut search_matches,
+            observer,
+            cancel_token,
+        );
+        match controller_result {
+            Ok(()) => observer.on_event(&SearchEvent::ControllerFinished { name: controller.display_name() }),
+            Err(UnifiAPIError::Canceled) => return Err(UnifiAPIError::Canceled),
+            Err(e) if controller_count == 1 => return Err(e),
+            Err(e) => {
+                observer.on_event(&SearchEvent::ControllerFailed { name: controller.display_name(), error: &e });
+                search_matches.failed_controllers.push(FailedController {
+                    name: Box::from(controller.display_name()),
+                    error: e.to_string().into_boxed_str(),
+                });
+                first_error_option.get_or_insert(e);
+            }
+        }
+
+        // an exact search stops at the first match
+        if mac_query.is_exact() && !search_matches.is_empty() {
+            break;
+        }
+    }
+    match first_error_option {
+        Some(e) if search_matches.failed_controllers.len() == controller_count => Err(e),
+        _ => Ok(search_matches),
+    }
+}
+
+/// The parts of the search shared by every controller
+struct SearchQuery<'a> {
+    mac_query: &'a MacQuery,
+    device_type_filter: Option<&'a DeviceType>,
+    search_clients: bool,
+    proxy: &'a ProxyMode,
+    all_sites: bool,
+}
+
+/// Searches every site in one controller, adding its matches & sites to `search_matches`
+fn search_controller(
+    controller: &mut UnifiController,
+    controller_name: &str,
+    search_query: &SearchQuery,
+    search_matches: &mut UnifiSearchMatches,
+    observer: &mut dyn SearchObserver,
+    cancel_token: &CancellationToken,
+) -> Result<(), UnifiAPIError> {
+    let SearchQuery {
+        mac_query,
+        device_type_filter,
+        search_clients,
+        proxy,
+        all_sites,
+    } = *search_query;
+    let UnifiController {
         username,
         password,
         ref server_url,
-        ref mac_to_search,
         ref accept_invalid_certs,
-    } = search_info;
+        ref site_filter,
+        ..
+    } = controller;
 
-    let mut client = get_client_and_login(username, password, server_url, *accept_invalid_certs)?;
+    let client_builder = UnifiClient::builder(server_url)
+        .accept_invalid_certs(*accept_invalid_certs)
+        .proxy(proxy.clone());
+    let client = get_client_and_login(client_builder, username, password, cancel_token)?;
+    observer.on_event(&SearchEvent::ServerUrlResolved {
+        url: client.server_url(),
+        probed: client.server_url_was_probed(),
+    });
+    observer.on_event(&SearchEvent::LoggedIn);
 
-    // check for cancel signal; if channel empty, move on
-    if let Ok(v) = search_thread_channels.signal_rx.try_recv() {
-        if v == CancelSignal {
-            return Ok(None);
-        }
+    // the search doesn't depend on the controller info, so it continues if it can't be requested
+    match client.get_controller_info() {
+        Ok(controller_info) => observer.on_event(&SearchEvent::ControllerIdentified(&controller_info)),
+        Err(UnifiAPIError::Canceled) => return Err(UnifiAPIError::Canceled),
+        Err(_) => {}
     }
 
-    let mac_to_search = *mac_to_search;
-    let mut unifi_sites = client.get_sites()?;
-    let unifi_sites_len = unifi_sites.len() as f32;
+    // check for cancellation after the login, which can be slow
+    if cancel_token.is_canceled() {
+        return Err(UnifiAPIError::Canceled);
+    }
 
-    for (iter_num, site) in unifi_sites.iter_mut().enumerate() {
-        // check for cancel signal each iteration
-        if let Ok(v) = search_thread_channels.signal_rx.try_recv() {
-            if v == CancelSignal {
-                return Ok(None);
-            }
-        }
+    // clients don't have a device type, so they're skipped when searching for a specific device type
+    let search_clients = search_clients && device_type_filter.is_none();
+    let (mut unifi_sites, sites_total) = get_sites_to_search(&client, all_sites)?;
+    let sites_listed = unifi_sites.len();
+    unifi_sites.retain(|site| site_filter.matches(site));
+    for site in unifi_sites.iter_mut() {
+        site.controller = Box::from(controller_name);
+    }
+    observer.on_event(&SearchEvent::SitesListed(unifi_sites.len()));
+    search_matches.sites.add(SiteCoverage {
+        searched: 0,
+        total: sites_total,
+        excluded: sites_listed - unifi_sites.len(),
+    });
 
-        {
-            // send percentage of search completion to GUI thread
-            let _ = search_thread_channels
-                .percentage_tx
-                .try_send(iter_num as f32 / unifi_sites_len);
+    for site in unifi_sites.iter() {
+        // check for cancellation each iteration
+        if cancel_token.is_canceled() {
+            return Err(UnifiAPIError::Canceled);
         }
+        observer.on_event(&SearchEvent::SiteStarted(site));
 
         // get devices from a specific site
-        let site_devices = client.get_site_devices_basic(&site.code)?;
-        let unifi_device_option = find_device_simd(site_devices, mac_to_search);
+        let site_devices = with_retries(site, observer, cancel_token, || client.get_site_devices_basic(&site.code))?;
+        let site_device_count = site_devices.len();
+        for unifi_device in site_devices.iter().filter(|device| !device.has_known_label()) {
+            observer.on_event(&SearchEvent::UnknownModel { site, device: unifi_device });
+        }
+        let site_device_matches = filter_devices_simd(site_devices, mac_query, device_type_filter);
+        let devices_start = search_matches.devices.len();
+        search_matches.devices.extend(site_device_matches.into_iter().map(|mut unifi_device| {
+            unifi_device.create_device_label();
+            unifi_device.site = site.clone();
+            unifi_device
+        }));
+        for unifi_device in &search_matches.devices[devices_start..] {
+            observer.on_event(&SearchEvent::Match(SearchMatch::Device(unifi_device)));
+        }
 
-        if let Some(mut unifi_device) = unifi_device_option {
-            {
-                // set percentage to 100% since we got a match
-                let _ = search_thread_channels.percentage_tx.try_send(1f32);
+        // get clients from a specific site, unless an exact search already found a device
+        if search_clients && !(mac_query.is_exact() && !search_matches.is_empty()) {
+            let site_clients = with_retries(site, observer, cancel_token, || client.get_site_clients_all(&site.code))?;
+            let site_client_matches = filter_clients_simd(site_clients, mac_query);
+            let clients_start = search_matches.clients.len();
+            search_matches.clients.extend(site_client_matches.into_iter().map(|mut client_device| {
+                client_device.site = site.clone();
+                client_device
+            }));
+            for client_device in &search_matches.clients[clients_start..] {
+                observer.on_event(&SearchEvent::Match(SearchMatch::Client(client_device)));
             }
+        }
 
-            unifi_device.create_device_label();
-            unifi_device.site = std::mem::take(&mut site.desc);
-            return Ok(Some(unifi_device));
+        search_matches.sites.searched += 1;
+        observer.on_event(&SearchEvent::SiteFinished(site, site_device_count));
+
+        // an exact search stops at the first match
+        if mac_query.is_exact() && !search_matches.is_empty() {
+            return Ok(());
         }
     }
-    Ok(None)
+    Ok(())
 }
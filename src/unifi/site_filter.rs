@@ -0,0 +1,257 @@
+use super::devices::UnifiSite;
+use regex_automata::{meta::Regex, util::syntax};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Characters with a special meaning in a regex, escaped when converting a glob
+const REGEX_META_CHARACTERS: &str = r"\.+*?()|[]{}^$#&-~";
+
+/// Error parsing a site pattern
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid site pattern {pattern:?}, {reason}")]
+pub struct SiteFilterError {
+    pub pattern: Box<str>,
+    pub reason: Box<str>,
+}
+
+/// Which part of the site a pattern is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteField {
+    /// Either the code or the description
+    Any,
+    /// `UnifiSite::code`, ie `default`
+    Code,
+    /// `UnifiSite::desc`, the name shown in the controller
+    Desc,
+}
+
+/// A glob or regex matched against a site, written as `[code:|desc:][re:]pattern`.
+///
+/// Globs use `*` for any number of characters & `?` for a single character, & must match the whole code or description.
+/// Regexes only need to match part of it. Both ignore case.
+/// ie `lab-*`, `desc:*Archive*`, or `code:re:^cust-\d+$`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SitePattern {
+    /// The pattern as it was written, kept for displaying & saving it
+    text: Box<str>,
+    field: SiteField,
+    regex: Regex,
+}
+
+impl SitePattern {
+    #[inline]
+    pub fn field(&self) -> SiteField {
+        self.field
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    #[inline]
+    pub fn matches(&self, site: &UnifiSite) -> bool {
+        match self.field {
+            SiteField::Any => self.regex.is_match(&*site.code) || self.regex.is_match(&*site.desc),
+            SiteField::Code => self.regex.is_match(&*site.code),
+            SiteField::Desc => self.regex.is_match(&*site.desc),
+        }
+    }
+
+    /// One pattern per line, blank lines are skipped
+    pub fn parse_lines(text: &str) -> Result<Vec<SitePattern>, SiteFilterError> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(SitePattern::from_str)
+            .collect()
+    }
+}
+
+impl FromStr for SitePattern {
+    type Err = SiteFilterError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid_pattern = |reason: String| SiteFilterError {
+            pattern: Box::from(text),
+            reason: reason.into_boxed_str(),
+        };
+        let (field, rest) = if let Some(rest) = text.strip_prefix("code:") {
+            (SiteField::Code, rest)
+        } else if let Some(rest) = text.strip_prefix("desc:") {
+            (SiteField::Desc, rest)
+        } else {
+            (SiteField::Any, text)
+        };
+        let regex_text = match rest.strip_prefix("re:") {
+            Some(regex_text) => regex_text.to_string(),
+            None => glob_to_regex(rest),
+        };
+        if rest.is_empty() || regex_text.is_empty() {
+            return Err(invalid_pattern(String::from("the pattern is empty")));
+        }
+        let regex = Regex::builder()
+            .syntax(syntax::Config::new().case_insensitive(true))
+            .build(&regex_text)
+            // the syntax error explains where the regex is invalid, the build error only says which pattern
+            .map_err(|e| invalid_pattern(e.syntax_error().map_or_else(|| e.to_string(), ToString::to_string)))?;
+        Ok(Self {
+            text: Box::from(text),
+            field,
+            regex,
+        })
+    }
+}
+
+impl TryFrom<String> for SitePattern {
+    type Error = SiteFilterError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<SitePattern> for String {
+    fn from(site_pattern: SitePattern) -> Self {
+        site_pattern.text.into_string()
+    }
+}
+
+impl PartialEq for SitePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl std::fmt::Display for SitePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// `*` & `?` become `.*` & `.`, anchored so the glob matches the whole text
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::with_capacity(glob.len() + 2);
+    regex.push('^');
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c if REGEX_META_CHARACTERS.contains(c) => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Sites to search, applied to the site list before any site is searched.
+/// A site is searched if it matches any include pattern (or there are none) & doesn't match any exclude pattern
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteFilter {
+    pub include: Vec<SitePattern>,
+    pub exclude: Vec<SitePattern>,
+}
+
+impl SiteFilter {
+    pub fn new(include: Vec<SitePattern>, exclude: Vec<SitePattern>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// An empty filter searches every site
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    #[inline]
+    pub fn matches(&self, site: &UnifiSite) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(site)))
+            && !self.exclude.iter().any(|pattern| pattern.matches(site))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(code: &str, desc: &str) -> UnifiSite {
+        UnifiSite {
+            code: Box::from(code),
+            desc: Box::from(desc),
+            controller: Box::default(),
+        }
+    }
+
+    fn pattern(text: &str) -> SitePattern {
+        text.parse().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn globs_are_escaped_and_anchored() {
+        assert_eq!(glob_to_regex("lab-*"), r"^lab\-.*$");
+        assert_eq!(glob_to_regex("site.?"), r"^site\..$");
+        assert_eq!(glob_to_regex("a+b (c) [d] {e} ^$|#&~\\"), r"^a\+b \(c\) \[d\] \{e\} \^\$\|\#\&\~\\$");
+        assert_eq!(glob_to_regex("café"), "^café$");
+    }
+
+    #[test]
+    fn globs_match_the_whole_code_or_description() {
+        let lab = pattern("lab-*");
+        assert!(lab.matches(&site("lab-1", "Main Office")));
+        assert!(lab.matches(&site("default", "LAB-Racks")));
+        assert!(!lab.matches(&site("xlab-1", "Main lab-1")));
+
+        let single = pattern("site?");
+        assert!(single.matches(&site("site1", "")));
+        assert!(!single.matches(&site("site12", "")));
+
+        // meta characters in a glob are literal
+        let dotted = pattern("a.b");
+        assert!(dotted.matches(&site("a.b", "")));
+        assert!(!dotted.matches(&site("axb", "")));
+    }
+
+    #[test]
+    fn field_and_regex_prefixes() {
+        let desc = pattern("desc:*Archive*");
+        assert_eq!(desc.field(), SiteField::Desc);
+        assert!(desc.matches(&site("abc", "Old archive 2019")));
+        assert!(!desc.matches(&site("archive", "Old")));
+
+        let code = pattern(r"code:re:^emea-\d+");
+        assert_eq!(code.field(), SiteField::Code);
+        assert!(code.matches(&site("EMEA-12x", "")));
+        assert!(!code.matches(&site("apac-1", "emea-1")));
+
+        // regexes only need to match part of the text
+        assert!(pattern("re:office").matches(&site("x", "Main Office 2")));
+        assert_eq!(pattern("re:office").as_str(), "re:office");
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_patterns() {
+        for text in ["", "code:", "desc:re:", "re:", "re:(unclosed"] {
+            assert!(text.parse::<SitePattern>().is_err(), "{text}");
+        }
+        assert!(SitePattern::parse_lines("lab-*\n\n  desc:x  \n").is_ok_and(|patterns| patterns.len() == 2));
+    }
+
+    #[test]
+    fn include_then_exclude() {
+        let filter = SiteFilter::new(vec![pattern("emea-*")], vec![pattern("*-lab")]);
+        assert!(filter.matches(&site("emea-1", "")));
+        assert!(!filter.matches(&site("emea-lab", "")));
+        assert!(!filter.matches(&site("apac-1", "")));
+
+        let exclude_only = SiteFilter::new(Vec::new(), vec![pattern("*-lab")]);
+        assert!(exclude_only.matches(&site("apac-1", "")));
+        assert!(SiteFilter::default().matches(&site("anything", "")));
+    }
+}
@@ -0,0 +1,217 @@
+use super::{devices::UnifiDeviceBasic, progress::SearchEvent};
+use crate::{csv::csv_field, mac_address::MacAddress};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+use thiserror::Error;
+
+const UNKNOWN_MODELS_FILE_NAME: &str = "unknown_models.json";
+const UNKNOWN_MODELS_CSV_FILE_NAME: &str = "unknown_models.csv";
+
+/// Error reading or writing the unknown models files
+#[derive(Error, Debug)]
+pub enum UnknownModelsError {
+    #[error("Unable to access file {path}\n{source}")]
+    Io {
+        path: Box<str>,
+        source: io::Error,
+    },
+    #[error("Unable to parse file {path}\n{source}")]
+    Json {
+        path: Box<str>,
+        source: serde_json::Error,
+    },
+}
+
+/// A device type & model code combination that has no entry in `device_label`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownModel {
+    pub device_type: Box<str>,
+    pub device_model: Box<str>,
+    pub first_seen_site: Box<str>,
+    /// Number of different devices seen with this model
+    pub count: u64,
+    /// MAC Addresses of the devices counted, so searching the same site again doesn't count them twice.
+    /// Only grows with the number of different devices of this model
+    #[serde(default)]
+    pub seen_macs: BTreeSet<MacAddress>,
+}
+
+/// Persistent list of every unknown device model seen during searches, stored next to the executable
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownModels {
+    models: Vec<UnknownModel>,
+}
+
+impl UnknownModels {
+    /// Location of the persistent list; falls back to the working directory if the executable path is unavailable
+    pub fn file_path() -> PathBuf {
+        data_file_path(UNKNOWN_MODELS_FILE_NAME)
+    }
+
+    /// Loads the persistent list, an empty list is returned if the file doesn't exist yet
+    pub fn load() -> Result<Self, UnknownModelsError> {
+        let path = Self::file_path();
+        match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|source| UnknownModelsError::Json {
+                path: path.display().to_string().into_boxed_str(),
+                source,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(UnknownModelsError::Io {
+                path: path.display().to_string().into_boxed_str(),
+                source,
+            }),
+        }
+    }
+
+    /// Overwrites `unknown_models.json` with every model recorded so far
+    pub fn save(&self) -> Result<(), UnknownModelsError> {
+        let path = Self::file_path();
+        let json = serde_json::to_vec_pretty(self).map_err(|source| UnknownModelsError::Json {
+            path: path.display().to_string().into_boxed_str(),
+            source,
+        })?;
+        fs::write(&path, json).map_err(|source| UnknownModelsError::Io {
+            path: path.display().to_string().into_boxed_str(),
+            source,
+        })
+    }
+
+    #[inline]
+    pub fn models(&self) -> &[UnknownModel] {
+        &self.models
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Records the device of a `SearchEvent::UnknownModel`, other events are ignored
+    #[inline]
+    pub fn record_event(&mut self, event: &SearchEvent) {
+        if let SearchEvent::UnknownModel { site, device } = event {
+            self.record(device, &site.desc);
+        }
+    }
+
+    /// Counts the device under its model, adding the model if this is the first time it's been seen.
+    /// A device that was already counted is skipped
+    pub fn record(&mut self, device: &UnifiDeviceBasic, site_desc: &str) {
+        let device_type = device.device_type.as_str();
+        match self.models.iter_mut().find(|m| {
+            &*m.device_type == device_type && m.device_model == device.device_model
+        }) {
+            Some(unknown_model) => {
+                if unknown_model.seen_macs.insert(device.mac) {
+                    unknown_model.count += 1;
+                }
+            }
+            None => self.models.push(UnknownModel {
+                device_type: Box::from(device_type),
+                device_model: device.device_model.clone(),
+                first_seen_site: Box::from(site_desc),
+                count: 1,
+                seen_macs: BTreeSet::from([device.mac]),
+            }),
+        }
+    }
+
+    /// Writes the list in the same format as `src/misc/unifi_models_to_names.csv`, with the SKU & Name left blank.
+    /// Returns the path of the written file.
+    pub fn export_csv(&self) -> Result<PathBuf, UnknownModelsError> {
+        let path = data_file_path(UNKNOWN_MODELS_CSV_FILE_NAME);
+        let map_io_err = |source| UnknownModelsError::Io {
+            path: path.display().to_string().into_boxed_str(),
+            source,
+        };
+        let mut file = io::BufWriter::new(fs::File::create(&path).map_err(map_io_err)?);
+        self.write_csv(&mut file).map_err(map_io_err)?;
+        file.flush().map_err(map_io_err)?;
+        Ok(path)
+    }
+
+    fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        // the models csv starts with a UTF-8 byte order mark
+        writer.write_all("\u{feff}Model,Type,SKU,Name\n".as_bytes())?;
+        let mut models: Vec<&UnknownModel> = self.models.iter().collect();
+        models.sort_by(|a, b| (&a.device_type, &a.device_model).cmp(&(&b.device_type, &b.device_model)));
+        for m in models {
+            writeln!(writer, "{},{},,", csv_field(&m.device_model), csv_field(&m.device_type))?;
+        }
+        Ok(())
+    }
+}
+
+/// Path for a data file stored alongside the executable
+pub fn data_file_path(file_name: &str) -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(file_name)))
+        .unwrap_or_else(|| PathBuf::from(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(mac: &str, model: &str) -> UnifiDeviceBasic {
+        serde_json::from_value(serde_json::json!({
+            "mac": mac,
+            "state": 1,
+            "adopted": true,
+            "type": "uap",
+            "model": model,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_each_device_once() {
+        let mut unknown_models = UnknownModels::default();
+        for _ in 0..2 {
+            unknown_models.record(&device("74:83:c2:3f:a2:10", "UNKNOWN1"), "Main Office");
+            unknown_models.record(&device("74:83:c2:3f:a2:12", "UNKNOWN1"), "Branch");
+            unknown_models.record(&device("74:83:c2:3f:a2:14", "UNKNOWN2"), "Branch");
+        }
+        let counts: Vec<(&str, &str, u64)> = unknown_models
+            .models()
+            .iter()
+            .map(|m| (&*m.device_model, &*m.first_seen_site, m.count))
+            .collect();
+        assert_eq!(counts, [("UNKNOWN1", "Main Office", 2), ("UNKNOWN2", "Branch", 1)]);
+    }
+
+    #[test]
+    fn saved_macs_are_not_counted_again() {
+        let mut unknown_models = UnknownModels::default();
+        unknown_models.record(&device("74:83:c2:3f:a2:10", "UNKNOWN1"), "Main Office");
+        let json = serde_json::to_string(&unknown_models).unwrap();
+        let mut unknown_models: UnknownModels = serde_json::from_str(&json).unwrap();
+        unknown_models.record(&device("74:83:c2:3f:a2:10", "UNKNOWN1"), "Main Office");
+        assert_eq!(unknown_models.models()[0].count, 1);
+        assert_eq!(unknown_models.models()[0].seen_macs.len(), 1);
+    }
+
+    #[test]
+    fn reads_lists_saved_without_macs() {
+        let json = r#"{"models": [{"device_type": "uap", "device_model": "OLD", "first_seen_site": "Main", "count": 3}]}"#;
+        let mut unknown_models: UnknownModels = serde_json::from_str(json).unwrap();
+        unknown_models.record(&device("74:83:c2:3f:a2:10", "OLD"), "Main");
+        assert_eq!(unknown_models.models()[0].count, 4);
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        let mut unknown_models = UnknownModels::default();
+        unknown_models.record(&device("74:83:c2:3f:a2:10", "U7,\"Pro\""), "Main");
+        let mut csv = Vec::new();
+        unknown_models.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "\u{feff}Model,Type,SKU,Name\n\"U7,\"\"Pro\"\"\",uap,,\n");
+    }
+}